@@ -0,0 +1,80 @@
+//! Writes a page to an I2C EEPROM at address 0x50 and reads it back, using DMA
+
+#![no_main]
+#![no_std]
+
+use panic_semihosting as _;
+
+use cortex_m::{asm, singleton};
+
+use cortex_m_rt::entry;
+use gd32e103_hal::{
+    i2c::{BlockingI2c, DutyCycle, Mode},
+    pac,
+    prelude::*,
+};
+
+const EEPROM_ADDRESS: u8 = 0x50;
+
+#[entry]
+fn main() -> ! {
+    // Get access to the core peripherals from the cortex-m crate
+    let mut cp = cortex_m::Peripherals::take().unwrap();
+    // Get access to the device specific peripherals from the peripheral access crate
+    let p = pac::Peripherals::take().unwrap();
+
+    let mut flash = p.FMC.constrain();
+    let mut rcu = p.RCU.constrain();
+
+    let clocks = rcu.cfgr.freeze(&mut flash.ws);
+
+    let channels = p.DMA0.split(&mut rcu.ahb);
+
+    let mut gpiob = p.GPIOB.split(&mut rcu.apb2);
+
+    let scl = gpiob.pb6.into_alternate_open_drain(&mut gpiob.crl);
+    let sda = gpiob.pb7.into_alternate_open_drain(&mut gpiob.crl);
+
+    let i2c = BlockingI2c::i2c0(
+        p.I2C0,
+        scl,
+        sda,
+        Mode::fast(400_000.hz(), DutyCycle::Ratio2to1),
+        &clocks,
+        &mut rcu.apb1,
+        &mut cp.DCB,
+        &mut cp.DWT,
+        1000.us(),
+        10,
+        1000.us(),
+        1000.us(),
+    );
+
+    // The first byte is the memory address to write the page to
+    let page = singleton!(: [u8; 17] = [0; 17]).unwrap();
+    for (i, byte) in page.iter_mut().enumerate().skip(1) {
+        *byte = i as u8;
+    }
+
+    let tx = i2c.with_tx_dma(channels.5);
+    let (_page, tx) = match tx.write(EEPROM_ADDRESS, page) {
+        Ok(transfer) => transfer.wait(),
+        Err(_) => panic!("EEPROM did not respond"),
+    };
+
+    // Wait for the EEPROM to finish writing the page, then set the memory address to read from
+    let (mut i2c, _) = tx.split();
+    while i2c.write(EEPROM_ADDRESS, &[0]).is_err() {}
+
+    let rx = i2c.with_rx_dma(channels.6);
+    let buf = singleton!(: [u8; 16] = [0; 16]).unwrap();
+    let (_buf, _rx) = match rx.read(EEPROM_ADDRESS, buf) {
+        Ok(transfer) => transfer.wait(),
+        Err(_) => panic!("EEPROM did not respond"),
+    };
+
+    asm::bkpt();
+
+    #[allow(clippy::empty_loop)]
+    loop {}
+}
@@ -0,0 +1,111 @@
+//! Polls a temperature sensor at address 0x48 with an interrupt-driven I2C master, leaving the CPU
+//! free while each transaction is in progress.
+
+#![no_main]
+#![no_std]
+
+use panic_semihosting as _;
+
+use gd32e103_hal::{
+    gpio::{gpiob, Alternate, OpenDrain},
+    i2c::{AsyncI2c, I2c, Mode},
+    pac::{interrupt, Interrupt, Peripherals, I2C0},
+    prelude::*,
+};
+
+use core::cell::RefCell;
+use cortex_m::{asm::wfi, interrupt::Mutex, singleton};
+use cortex_m_rt::entry;
+
+const SENSOR_ADDRESS: u8 = 0x48;
+
+type Sensor = AsyncI2c<I2C0, gpiob::PB6<Alternate<OpenDrain>>, gpiob::PB7<Alternate<OpenDrain>>>;
+
+// Make the I2C master available to both the main thread and the interrupt handlers
+static G_I2C: Mutex<RefCell<Option<Sensor>>> = Mutex::new(RefCell::new(None));
+
+fn on_i2c_interrupt() {
+    cortex_m::interrupt::free(|cs| {
+        if let Some(i2c) = G_I2C.borrow(cs).borrow_mut().as_mut() {
+            i2c.on_interrupt();
+        }
+    });
+}
+
+#[interrupt]
+fn I2C0_EV() {
+    on_i2c_interrupt();
+}
+
+#[interrupt]
+fn I2C0_ER() {
+    on_i2c_interrupt();
+}
+
+#[entry]
+fn main() -> ! {
+    // Get access to the device specific peripherals from the peripheral access crate
+    let dp = Peripherals::take().unwrap();
+
+    let mut flash = dp.FMC.constrain();
+    let mut rcu = dp.RCU.constrain();
+    let clocks = rcu.cfgr.freeze(&mut flash.ws);
+
+    let mut gpiob = dp.GPIOB.split(&mut rcu.apb2);
+
+    let scl = gpiob.pb6.into_alternate_open_drain(&mut gpiob.crl);
+    let sda = gpiob.pb7.into_alternate_open_drain(&mut gpiob.crl);
+
+    let i2c = I2c::i2c0(
+        dp.I2C0,
+        scl,
+        sda,
+        Mode::standard(100_000.hz()),
+        &clocks,
+        &mut rcu.apb1,
+    );
+    cortex_m::interrupt::free(|cs| *G_I2C.borrow(cs).borrow_mut() = Some(AsyncI2c::new(i2c)));
+
+    unsafe {
+        cortex_m::peripheral::NVIC::unmask(Interrupt::I2C0_EV);
+        cortex_m::peripheral::NVIC::unmask(Interrupt::I2C0_ER);
+    }
+
+    // The temperature register index, and the buffer its value is read into
+    let register: &'static [u8] = singleton!(: [u8; 1] = [0]).unwrap();
+    let temperature: &'static mut [u8] = singleton!(: [u8; 2] = [0; 2]).unwrap();
+    let mut buffers = Some((register, temperature));
+
+    loop {
+        cortex_m::interrupt::free(|cs| {
+            if let Some(i2c) = G_I2C.borrow(cs).borrow_mut().as_mut() {
+                let (register, temperature) = buffers.take().unwrap();
+                i2c.write_read(SENSOR_ADDRESS, register, temperature);
+            }
+        });
+
+        let completion = loop {
+            // Waiting with interrupts masked can't miss the completing interrupt, which still wakes
+            // the core and is handled once the critical section ends
+            let completion = cortex_m::interrupt::free(|cs| {
+                let completion = G_I2C
+                    .borrow(cs)
+                    .borrow_mut()
+                    .as_mut()
+                    .and_then(|i2c| i2c.poll());
+                if completion.is_none() {
+                    wfi();
+                }
+                completion
+            });
+            if let Some(completion) = completion {
+                break completion;
+            }
+        };
+
+        if completion.result.is_ok() {
+            let _celsius = i16::from_be_bytes([completion.read[0], completion.read[1]]) >> 8;
+        }
+        buffers = Some((completion.write, completion.read));
+    }
+}
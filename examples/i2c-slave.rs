@@ -0,0 +1,68 @@
+//! I2C slave exposing a bank of registers at address 0x42
+//!
+//! The master writes a register index followed by data to store, or writes a register index and
+//! then reads from it with a repeated start.
+
+#![deny(unsafe_code)]
+#![no_main]
+#![no_std]
+
+use panic_semihosting as _;
+
+use cortex_m_rt::entry;
+use gd32e103_hal::{
+    i2c::{I2cSlave, SlaveConfig, SlaveEvent},
+    pac,
+    prelude::*,
+};
+
+#[entry]
+fn main() -> ! {
+    // Get access to the device specific peripherals from the peripheral access crate
+    let p = pac::Peripherals::take().unwrap();
+
+    let mut flash = p.FMC.constrain();
+    let mut rcu = p.RCU.constrain();
+
+    let clocks = rcu.cfgr.freeze(&mut flash.ws);
+
+    let mut gpiob = p.GPIOB.split(&mut rcu.apb2);
+
+    let scl = gpiob.pb6.into_alternate_open_drain(&mut gpiob.crl);
+    let sda = gpiob.pb7.into_alternate_open_drain(&mut gpiob.crl);
+
+    let mut i2c = I2cSlave::i2c0(
+        p.I2C0,
+        scl,
+        sda,
+        SlaveConfig::seven_bit(0x42).general_call(true),
+        &clocks,
+        &mut rcu.apb1,
+    );
+
+    let mut registers = [0u8; 16];
+    let mut index = None;
+    let mut pointer = 0;
+
+    loop {
+        match nb::block!(i2c.event()) {
+            Ok(SlaveEvent::AddressMatched { read: false, .. }) => index = None,
+            Ok(SlaveEvent::AddressMatched { read: true, .. }) => {}
+            Ok(SlaveEvent::ByteReceived(byte)) => match index {
+                None => {
+                    pointer = usize::from(byte) % registers.len();
+                    index = Some(pointer);
+                }
+                Some(_) => {
+                    registers[pointer] = byte;
+                    pointer = (pointer + 1) % registers.len();
+                }
+            },
+            Ok(SlaveEvent::ByteRequested) => {
+                i2c.write(registers[pointer]);
+                pointer = (pointer + 1) % registers.len();
+            }
+            Ok(SlaveEvent::Stop) | Err(_) => {}
+        }
+    }
+}
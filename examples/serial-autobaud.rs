@@ -0,0 +1,65 @@
+//! Serial interface automatic baud rate detection
+//!
+//! The host must send 0x55 or 0x7F first, at any baud rate. The start bit is timed by TIMER0
+//! channel 2, which shares PA10 with the USART0 RX.
+
+#![deny(unsafe_code)]
+#![no_main]
+#![no_std]
+
+use panic_semihosting as _;
+
+use cortex_m::asm;
+
+use nb::block;
+
+use cortex_m_rt::entry;
+use gd32e103_hal::{
+    pac,
+    prelude::*,
+    serial::{Config, Serial},
+    timer::Timer,
+};
+
+#[entry]
+fn main() -> ! {
+    // Get access to the device specific peripherals from the peripheral access crate.
+    let p = pac::Peripherals::take().unwrap();
+
+    // Take ownership of the RCU and FMC peripherals and convert them into the corresponding HAL
+    // structs.
+    let mut rcu = p.RCU.constrain();
+    let mut flash = p.FMC.constrain();
+
+    // Freeze the configuration of all the clocks in the system and store the frozen frequencies in
+    // `clocks`.
+    let clocks = rcu.cfgr.freeze(&mut flash.ws);
+
+    // Prepare the GPIOA peripheral
+    let mut gpioa = p.GPIOA.split(&mut rcu.apb2);
+
+    // USART0
+    let tx = gpioa.pa9.into_alternate_push_pull(&mut gpioa.crh);
+    let rx = gpioa.pa10.into_alternate_push_pull(&mut gpioa.crh);
+
+    let mut serial = Serial::usart(p.USART0, (tx, rx), Config::default(), clocks, &mut rcu.apb2);
+
+    // Wait for the sync byte and switch to its baud rate, retrying until a valid one is received.
+    let mut timer = Timer::timer0(p.TIMER0, &clocks, &mut rcu.apb2);
+    let _baud_rate = loop {
+        if let Ok(baud_rate) =
+            serial.detect_baudrate(&mut timer, Config::default(), &clocks, 1_000_000.us())
+        {
+            break baud_rate;
+        }
+    };
+
+    // Acknowledge the sync byte.
+    block!(serial.write(0x79u8)).ok();
+
+    // Trigger a breakpoint to allow us to inspect the values
+    asm::bkpt();
+
+    #[allow(clippy::empty_loop)]
+    loop {}
+}
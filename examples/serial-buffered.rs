@@ -0,0 +1,92 @@
+//! Interrupt-driven serial communication through ring buffers
+//!
+//! Echoes everything received on USART0 back, and reports how many bytes have been lost.
+
+#![no_main]
+#![no_std]
+
+use panic_semihosting as _;
+
+use gd32e103_hal::{
+    pac::{interrupt, Interrupt, Peripherals, USART0},
+    prelude::*,
+    serial::{BufferedSerialInterrupt, Config, Serial, SerialBuffers},
+};
+
+use core::cell::RefCell;
+use core::fmt::Write;
+use cortex_m::{asm::wfi, interrupt::Mutex};
+use cortex_m_rt::entry;
+
+// Storage for the receive and transmit buffers
+static BUFFERS: SerialBuffers<256, 256> = SerialBuffers::new();
+
+// Make the interrupt half of the serial port available to the interrupt handler
+static G_SERIAL: Mutex<RefCell<Option<BufferedSerialInterrupt<USART0, 256, 256>>>> =
+    Mutex::new(RefCell::new(None));
+
+#[interrupt]
+fn USART0() {
+    static mut SERIAL: Option<BufferedSerialInterrupt<USART0, 256, 256>> = None;
+
+    let serial = SERIAL.get_or_insert_with(|| {
+        cortex_m::interrupt::free(|cs| {
+            // Move the interrupt half here, leaving a None in its place
+            G_SERIAL.borrow(cs).replace(None).unwrap()
+        })
+    });
+
+    serial.on_interrupt();
+}
+
+#[entry]
+fn main() -> ! {
+    let dp = Peripherals::take().unwrap();
+
+    let mut flash = dp.FMC.constrain();
+    let mut rcu = dp.RCU.constrain();
+    let clocks = rcu.cfgr.freeze(&mut flash.ws);
+
+    // Configure pa9 and pa10 in alternate function mode for the USART.
+    let mut gpioa = dp.GPIOA.split(&mut rcu.apb2);
+    let tx = gpioa.pa9.into_alternate_push_pull(&mut gpioa.crh);
+    let rx = gpioa.pa10.into_alternate_push_pull(&mut gpioa.crh);
+
+    let serial = Serial::usart(
+        dp.USART0,
+        (tx, rx),
+        Config::default().baudrate(921_600.bps()),
+        clocks,
+        &mut rcu.apb2,
+    );
+    let (mut serial, interrupt) = serial.into_buffered(&BUFFERS);
+
+    // Move the interrupt half into our global storage
+    cortex_m::interrupt::free(|cs| *G_SERIAL.borrow(cs).borrow_mut() = Some(interrupt));
+
+    unsafe {
+        cortex_m::peripheral::NVIC::unmask(Interrupt::USART0);
+    }
+
+    writeln!(serial, "Hello").unwrap();
+
+    let mut buffer = [0; 64];
+    let mut overruns = 0;
+    loop {
+        let count = serial.read(&mut buffer);
+        let mut data = &buffer[..count];
+        while !data.is_empty() {
+            let written = serial.write(data);
+            data = &data[written..];
+        }
+
+        if serial.overruns() != overruns {
+            overruns = serial.overruns();
+            writeln!(serial, "Lost {} bytes", overruns).unwrap();
+        }
+
+        if count == 0 {
+            wfi();
+        }
+    }
+}
@@ -45,7 +45,7 @@ fn main() -> ! {
         &mut rcu.apb2,
     );
 
-    let rx = serial.split().1.with_dma(channels.2);
+    let rx = serial.split().1.with_dma(channels.4);
     let buf = singleton!(: [[u8; 8]; 2] = [[0; 8]; 2]).unwrap();
 
     let mut circ_buffer = rx.circ_read(buf);
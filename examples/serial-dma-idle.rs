@@ -0,0 +1,57 @@
+//! Serial interface DMA reception of variable-length packets, each terminated by an idle line
+
+#![deny(unsafe_code)]
+#![no_main]
+#![no_std]
+
+use panic_semihosting as _;
+
+use cortex_m::{asm, singleton};
+
+use cortex_m_rt::entry;
+use gd32e103_hal::{
+    pac,
+    prelude::*,
+    serial::{Config, Serial},
+};
+
+#[entry]
+fn main() -> ! {
+    let p = pac::Peripherals::take().unwrap();
+
+    let mut flash = p.FMC.constrain();
+    let mut rcu = p.RCU.constrain();
+
+    let clocks = rcu.cfgr.freeze(&mut flash.ws);
+
+    let channels = p.DMA0.split(&mut rcu.ahb);
+
+    let mut gpioa = p.GPIOA.split(&mut rcu.apb2);
+
+    // USART0
+    let tx = gpioa.pa9.into_alternate_push_pull(&mut gpioa.crh);
+    let rx = gpioa.pa10.into_alternate_push_pull(&mut gpioa.crh);
+
+    let serial = Serial::usart(
+        p.USART0,
+        (tx, rx),
+        Config::default().baudrate(115_200.bps()),
+        clocks,
+        &mut rcu.apb2,
+    );
+
+    let rx = serial.split().1.with_dma(channels.4);
+    let buffers = singleton!(: [[u8; 64]; 2] = [[0; 64]; 2]).unwrap();
+
+    let mut receiver = rx.read_until_idle(buffers);
+
+    // The IDLE flag can be polled like this, or `on_idle` can be called from the USART0 interrupt
+    // handler instead.
+    loop {
+        if let Ok(Some(packet)) = receiver.on_idle() {
+            if packet == b"AT\r\n" {
+                asm::bkpt();
+            }
+        }
+    }
+}
@@ -38,7 +38,7 @@ fn main() -> ! {
 
     let serial = Serial::usart(p.USART0, (tx, rx), Config::default(), clocks, &mut rcu.apb2);
 
-    let rx = serial.split().1.with_dma(channels.2);
+    let rx = serial.split().1.with_dma(channels.4);
     let buf = singleton!(: [u8; 8] = [0; 8]).unwrap();
 
     let t = rx.read(buf);
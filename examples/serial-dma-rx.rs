@@ -44,7 +44,7 @@ fn main() -> ! {
         &mut rcu.apb2,
     );
 
-    let rx = serial.split().1.with_dma(channels.2);
+    let rx = serial.split().1.with_dma(channels.4);
     let buf = singleton!(: [u8; 8] = [0; 8]).unwrap();
 
     let (_buf, _rx) = rx.read(buf).wait();
@@ -44,7 +44,7 @@ fn main() -> ! {
         &mut rcu.apb2,
     );
 
-    let tx = serial.split().0.with_dma(channels.1);
+    let tx = serial.split().0.with_dma(channels.3);
 
     let (_, tx) = tx.write(b"The quick brown fox").wait();
 
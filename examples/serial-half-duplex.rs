@@ -0,0 +1,65 @@
+//! Half-duplex single-wire serial communication
+//!
+//! PA9 must be pulled up, either by the device on the other end of the wire or by an external
+//! resistor.
+
+#![deny(unsafe_code)]
+#![no_main]
+#![no_std]
+
+use panic_semihosting as _;
+
+use cortex_m::asm;
+
+use nb::block;
+
+use cortex_m_rt::entry;
+use gd32e103_hal::{
+    pac,
+    prelude::*,
+    serial::{Config, HalfDuplex},
+};
+
+#[entry]
+fn main() -> ! {
+    // Get access to the device specific peripherals from the peripheral access crate.
+    let p = pac::Peripherals::take().unwrap();
+
+    // Take ownership of the RCU and FMC peripherals and convert them into the corresponding HAL
+    // structs.
+    let mut rcu = p.RCU.constrain();
+    let mut flash = p.FMC.constrain();
+
+    // Freeze the configuration of all the clocks in the system and store the frozen frequencies in
+    // `clocks`.
+    let clocks = rcu.cfgr.freeze(&mut flash.ws);
+
+    // Prepare the GPIOA peripheral
+    let mut gpioa = p.GPIOA.split(&mut rcu.apb2);
+
+    // USART0 TX is used for both directions, so it must be open-drain.
+    let pin = gpioa.pa9.into_alternate_open_drain(&mut gpioa.crh);
+
+    let mut serial = HalfDuplex::usart(
+        p.USART0,
+        pin,
+        Config::default().baudrate(57_600.bps()),
+        clocks,
+        &mut rcu.apb2,
+    );
+
+    // Don't read back the bytes we send ourselves.
+    serial.set_discard_echo(true);
+
+    // Send a request, then wait for the reply.
+    for byte in b"ping" {
+        block!(serial.write(*byte)).ok();
+    }
+    let _reply: u8 = block!(serial.read()).unwrap();
+
+    // Trigger a breakpoint to allow us to inspect the values
+    asm::bkpt();
+
+    #[allow(clippy::empty_loop)]
+    loop {}
+}
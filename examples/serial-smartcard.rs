@@ -0,0 +1,64 @@
+//! Reading the answer to reset from an ISO 7816 smartcard
+//!
+//! PA9 is the card I/O line and must be pulled up. PA8 provides the card clock, and PA10 drives the
+//! card's reset line.
+
+#![deny(unsafe_code)]
+#![no_main]
+#![no_std]
+
+use panic_semihosting as _;
+
+use cortex_m::asm;
+
+use cortex_m_rt::entry;
+use gd32e103_hal::{
+    pac,
+    prelude::*,
+    serial::{Smartcard, SmartcardConfig, MAX_ATR_LENGTH},
+};
+
+#[entry]
+fn main() -> ! {
+    // Get access to the device specific peripherals from the peripheral access crate.
+    let p = pac::Peripherals::take().unwrap();
+
+    // Take ownership of the RCU and FMC peripherals and convert them into the corresponding HAL
+    // structs.
+    let mut rcu = p.RCU.constrain();
+    let mut flash = p.FMC.constrain();
+
+    // Freeze the configuration of all the clocks in the system and store the frozen frequencies in
+    // `clocks`.
+    let clocks = rcu.cfgr.freeze(&mut flash.ws);
+
+    // Prepare the GPIOA peripheral
+    let mut gpioa = p.GPIOA.split(&mut rcu.apb2);
+
+    // Hold the card in reset while the clock starts.
+    let mut reset = gpioa.pa10.into_push_pull_output(&mut gpioa.crh);
+    reset.set_low();
+
+    // USART0 TX is used for both directions, so it must be open-drain.
+    let io = gpioa.pa9.into_alternate_open_drain(&mut gpioa.crh);
+    let ck = gpioa.pa8.into_alternate_push_pull(&mut gpioa.crh);
+
+    let mut card = Smartcard::usart(
+        p.USART0,
+        (io, ck),
+        SmartcardConfig::default().clock(4.mhz()),
+        clocks,
+        &mut rcu.apb2,
+    );
+
+    // Release the reset line, and the card answers.
+    reset.set_high();
+    let mut buffer = [0; MAX_ATR_LENGTH];
+    let _atr = card.read_atr(&mut buffer).unwrap();
+
+    // Trigger a breakpoint to allow us to inspect the values
+    asm::bkpt();
+
+    #[allow(clippy::empty_loop)]
+    loop {}
+}
@@ -0,0 +1,82 @@
+//! Serial interface test between UART3 and UART4, which are only available on the 64-pin and
+//! 100-pin parts.
+//!
+//! You have to connect PC10 (UART3 TX) to PD2 (UART4 RX) and PC12 (UART4 TX) to PC11 (UART3 RX) to
+//! make this program work
+
+#![deny(unsafe_code)]
+#![no_main]
+#![no_std]
+
+use panic_semihosting as _;
+
+use cortex_m::asm;
+
+use nb::block;
+
+use cortex_m_rt::entry;
+use gd32e103_hal::{
+    pac,
+    prelude::*,
+    serial::{Config, Serial},
+};
+
+#[entry]
+fn main() -> ! {
+    // Get access to the device specific peripherals from the peripheral access crate.
+    let p = pac::Peripherals::take().unwrap();
+
+    // Take ownership of the RCU and FMC peripherals and convert them into the corresponding HAL
+    // structs.
+    let mut rcu = p.RCU.constrain();
+    let mut flash = p.FMC.constrain();
+
+    // Freeze the configuration of all the clocks in the system and store the frozen frequencies in
+    // `clocks`.
+    let clocks = rcu.cfgr.freeze(&mut flash.ws);
+
+    // Prepare the GPIOC and GPIOD peripherals
+    let mut gpioc = p.GPIOC.split(&mut rcu.apb2);
+    let mut gpiod = p.GPIOD.split(&mut rcu.apb2);
+
+    // UART3
+    let uart3_tx = gpioc.pc10.into_alternate_push_pull(&mut gpioc.crh);
+    let uart3_rx = gpioc.pc11.into_alternate_push_pull(&mut gpioc.crh);
+
+    // UART4
+    let uart4_tx = gpioc.pc12.into_alternate_push_pull(&mut gpioc.crh);
+    let uart4_rx = gpiod.pd2.into_alternate_push_pull(&mut gpiod.crl);
+
+    let mut uart3 = Serial::usart(
+        p.UART3,
+        (uart3_tx, uart3_rx),
+        Config::default().baudrate(9600.bps()),
+        clocks,
+        &mut rcu.apb1,
+    );
+    let mut uart4 = Serial::usart(
+        p.UART4,
+        (uart4_tx, uart4_rx),
+        Config::default().baudrate(9600.bps()),
+        clocks,
+        &mut rcu.apb1,
+    );
+
+    // Send a byte from UART3 to UART4.
+    let sent = b'X';
+    block!(uart3.write(sent)).ok();
+    let received: u8 = block!(uart4.read()).unwrap();
+    assert_eq!(received, sent);
+
+    // And back the other way.
+    let sent = b'Y';
+    block!(uart4.write(sent)).ok();
+    let received: u8 = block!(uart3.read()).unwrap();
+    assert_eq!(received, sent);
+
+    // Trigger a breakpoint to allow us to inspect the values
+    asm::bkpt();
+
+    #[allow(clippy::empty_loop)]
+    loop {}
+}
@@ -0,0 +1,77 @@
+//! Serial interface loopback test on USART2, using the full remap pins which are only available on
+//! the 100-pin parts.
+//!
+//! You have to short PD8 and PD9 to make this program work
+
+#![deny(unsafe_code)]
+#![no_main]
+#![no_std]
+
+use panic_semihosting as _;
+
+use cortex_m::asm;
+
+use nb::block;
+
+use cortex_m_rt::entry;
+use gd32e103_hal::{
+    pac,
+    prelude::*,
+    rcu::Enable,
+    serial::{Config, Serial},
+};
+
+#[entry]
+fn main() -> ! {
+    // Get access to the device specific peripherals from the peripheral access crate.
+    let p = pac::Peripherals::take().unwrap();
+
+    // Take ownership of the RCU and FMC peripherals and convert them into the corresponding HAL
+    // structs.
+    let mut rcu = p.RCU.constrain();
+    let mut flash = p.FMC.constrain();
+
+    // Freeze the configuration of all the clocks in the system and store the frozen frequencies in
+    // `clocks`.
+    let clocks = rcu.cfgr.freeze(&mut flash.ws);
+
+    // Fully remap USART2 to PD8 and PD9.
+    pac::AFIO::enable(&mut rcu.apb2);
+    #[allow(unsafe_code)]
+    p.AFIO
+        .pcf0
+        .modify(|_, w| unsafe { w.usart2_remap().bits(0b11) });
+
+    // Prepare the GPIOD peripheral
+    let mut gpiod = p.GPIOD.split(&mut rcu.apb2);
+
+    // Configure pd8 and pd9 in alternate function mode for the USART.
+    let tx = gpiod.pd8.into_alternate_push_pull(&mut gpiod.crh);
+    let rx = gpiod.pd9.into_alternate_push_pull(&mut gpiod.crh);
+
+    // Set up the usart device. Takes ownership of the USART registers and tx/rx pins. The rest of
+    // the registers are used to enable and configure the device.
+    let mut serial = Serial::usart(
+        p.USART2,
+        (tx, rx),
+        Config::default().baudrate(9600.bps()),
+        clocks,
+        &mut rcu.apb1,
+    );
+
+    // Loopback test. Write `X` and wait until the write is successful.
+    let sent = b'X';
+    block!(serial.write(sent)).ok();
+
+    // Read the byte that was just sent. Blocks until the read is complete
+    let received: u8 = block!(serial.read()).unwrap();
+
+    // Since we have connected tx and rx, the byte we sent should be the one we received
+    assert_eq!(received, sent);
+
+    // Trigger a breakpoint to allow us to inspect the values
+    asm::bkpt();
+
+    #[allow(clippy::empty_loop)]
+    loop {}
+}
@@ -0,0 +1,59 @@
+//! Reads the JEDEC ID of a SPI flash connected to SPI1, with its chip select on PB12
+
+#![deny(unsafe_code)]
+#![no_main]
+#![no_std]
+
+use panic_semihosting as _;
+
+use cortex_m_rt::entry;
+use cortex_m_semihosting::hprintln;
+use gd32e103_hal::{
+    pac,
+    prelude::*,
+    spi::{Mode, Phase, Polarity, Spi},
+};
+
+const READ_JEDEC_ID: u8 = 0x9F;
+
+#[entry]
+fn main() -> ! {
+    // Get access to the device specific peripherals from the peripheral access crate
+    let dp = pac::Peripherals::take().unwrap();
+
+    let mut flash = dp.FMC.constrain();
+    let mut rcu = dp.RCU.constrain();
+    let clocks = rcu.cfgr.freeze(&mut flash.ws);
+
+    let mut gpiob = dp.GPIOB.split(&mut rcu.apb2);
+
+    let mut cs = gpiob.pb12.into_push_pull_output(&mut gpiob.crh);
+    cs.set_high();
+
+    let pins = (
+        gpiob.pb13.into_alternate_push_pull(&mut gpiob.crh),
+        gpiob.pb14.into_floating_input(&mut gpiob.crh),
+        gpiob.pb15.into_alternate_push_pull(&mut gpiob.crh),
+    );
+
+    let mode = Mode {
+        polarity: Polarity::IdleLow,
+        phase: Phase::CaptureOnFirstTransition,
+    };
+    let mut spi = Spi::spi1(dp.SPI1, pins, mode, 1.mhz(), clocks, &mut rcu.apb1);
+
+    let mut buffer = [READ_JEDEC_ID, 0, 0, 0];
+    cs.set_low();
+    let id = spi.transfer(&mut buffer).unwrap();
+    cs.set_high();
+
+    hprintln!(
+        "manufacturer {:#04x}, device {:#04x}{:02x}",
+        id[1],
+        id[2],
+        id[3]
+    );
+
+    #[allow(clippy::empty_loop)]
+    loop {}
+}
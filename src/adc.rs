@@ -7,8 +7,8 @@
 //! Only ADC0 is supported
 
 use crate::dma::{
-    CircBuffer, CircReadDma, Priority, ReadDma, Receive, RxDma, Transfer, TransferPayload, Width,
-    C0, W,
+    dma0::C0, CircBuffer, CircReadDma, Priority, ReadDma, Receive, RxDma, Transfer,
+    TransferPayload, Width, W,
 };
 use crate::gpio::Analog;
 use crate::gpio::{gpioa, gpiob, gpioc};
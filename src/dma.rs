@@ -3,9 +3,8 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 //! # Direct Memory Access
-//! Only DMA0 is supported for now
 
-use crate::pac::{dma0 as dma, DMA0 as DMA};
+use crate::pac::dma0 as dma;
 use crate::rcu::{Enable, AHB};
 use core::{
     marker::PhantomData,
@@ -71,7 +70,7 @@ where
 {
     _mode: PhantomData<MODE>,
     buffer: BUFFER,
-    payload: PAYLOAD,
+    pub(crate) payload: PAYLOAD,
 }
 
 impl<BUFFER, PAYLOAD> Transfer<R, BUFFER, PAYLOAD>
@@ -134,7 +133,8 @@ pub(crate) enum Width {
 }
 
 macro_rules! dma {
-    {$($CX:ident: (
+    {$($DMAX:ident: ($dmaX:ident, {
+        $($CX:ident: (
             $htfifX:ident,
             $ftfifX:ident,
             $htfifcX:ident,
@@ -144,326 +144,334 @@ macro_rules! dma {
             $chXcnt:ident,
             $chXmaddr:ident,
             $chXpaddr:ident,
-    ),)+} => {
-        pub struct Channels($(pub $CX),+);
-
+        ),)+
+    }),)+} => {
         $(
-            /// A singleton that represents a single DMA channel (channel X in this case)
-            ///
-            /// This singleton has exclusive access to the registers of the DMA channel X
-            pub struct $CX {
-                _0: (),
-            }
+            pub mod $dmaX {
+                use super::*;
+                use crate::pac::$DMAX;
 
-            impl $CX {
-                /// Associated peripheral `address`
-                ///
-                /// `inc` indicates whether the address will be incremented after every byte transfer
-                pub fn set_peripheral_address(&mut self, address: u32, inc: bool) {
-                    unsafe { &(*DMA::ptr()).$chXpaddr }.write(|w| unsafe { w.paddr().bits(address) });
-                    unsafe { &(*DMA::ptr()).$chXctl }.modify(|_, w| w.pnaga().bit(inc));
-                }
+                pub struct Channels($(pub $CX),+);
 
-                /// `address` where from/to data will be read/write
-                ///
-                /// `inc` indicates whether the address will be incremented after every byte transfer
-                pub fn set_memory_address(&mut self, address: u32, inc: bool) {
-                    unsafe { &(*DMA::ptr()).$chXmaddr }.write(|w| unsafe { w.maddr().bits(address) });
-                    unsafe { &(*DMA::ptr()).$chXctl }.modify(|_, w| w.mnaga().bit(inc));
-                }
-
-                /// Number of bytes to transfer
-                pub fn set_transfer_length(&mut self, len: usize) {
-                    unsafe { &(*DMA::ptr()).$chXcnt }
-                        .write(|w| w.cnt().bits(cast::u16(len).unwrap()));
-                }
+                $(
+                    /// A singleton that represents a single DMA channel (channel X in this case)
+                    ///
+                    /// This singleton has exclusive access to the registers of the DMA channel X
+                    pub struct $CX {
+                        _0: (),
+                    }
 
-                /// Starts the DMA transfer
-                pub fn start(&mut self) {
-                    unsafe { &(*DMA::ptr()).$chXctl }.modify(|_, w| w.chen().enabled());
-                }
+                    impl $CX {
+                        /// Associated peripheral `address`
+                        ///
+                        /// `inc` indicates whether the address will be incremented after every byte transfer
+                        pub fn set_peripheral_address(&mut self, address: u32, inc: bool) {
+                            unsafe { &(*$DMAX::ptr()).$chXpaddr }.write(|w| unsafe { w.paddr().bits(address) });
+                            unsafe { &(*$DMAX::ptr()).$chXctl }.modify(|_, w| w.pnaga().bit(inc));
+                        }
 
-                /// Stops the DMA transfer
-                pub fn stop(&mut self) {
-                    self.intc().write(|w| w.$gifcX().clear());
-                    unsafe { &(*DMA::ptr()).$chXctl }.modify(|_, w| w.chen().disabled());
-                }
+                        /// `address` where from/to data will be read/write
+                        ///
+                        /// `inc` indicates whether the address will be incremented after every byte transfer
+                        pub fn set_memory_address(&mut self, address: u32, inc: bool) {
+                            unsafe { &(*$DMAX::ptr()).$chXmaddr }.write(|w| unsafe { w.maddr().bits(address) });
+                            unsafe { &(*$DMAX::ptr()).$chXctl }.modify(|_, w| w.mnaga().bit(inc));
+                        }
 
-                /// Returns `true` if there's a transfer in progress
-                pub fn in_progress(&self) -> bool {
-                    self.intf().$ftfifX().is_not_complete()
-                }
+                        /// Number of bytes to transfer
+                        pub fn set_transfer_length(&mut self, len: usize) {
+                            unsafe { &(*$DMAX::ptr()).$chXcnt }
+                                .write(|w| w.cnt().bits(cast::u16(len).unwrap()));
+                        }
 
-                pub fn listen(&mut self, event: Event) {
-                    match event {
-                        Event::HalfTransfer => unsafe { &(*DMA::ptr()).$chXctl }.modify(|_, w| w.htfie().enabled()),
-                        Event::TransferComplete => unsafe { &(*DMA::ptr()).$chXctl }.modify(|_, w| w.ftfie().enabled()),
-                    }
-                }
+                        /// Starts the DMA transfer
+                        pub fn start(&mut self) {
+                            unsafe { &(*$DMAX::ptr()).$chXctl }.modify(|_, w| w.chen().enabled());
+                        }
 
-                pub fn unlisten(&mut self, event: Event) {
-                    match event {
-                        Event::HalfTransfer => unsafe { &(*DMA::ptr()).$chXctl }.modify(|_, w| w.htfie().disabled()),
-                        Event::TransferComplete => unsafe { &(*DMA::ptr()).$chXctl }.modify(|_, w| w.ftfie().disabled()),
-                    }
-                }
+                        /// Stops the DMA transfer
+                        pub fn stop(&mut self) {
+                            self.intc().write(|w| w.$gifcX().clear());
+                            unsafe { &(*$DMAX::ptr()).$chXctl }.modify(|_, w| w.chen().disabled());
+                        }
 
-                /// Configures the DMA channel to transfer data from a peripheral to memory.
-                #[allow(dead_code)]
-                pub(crate) fn configure_from_peripheral(
-                    &mut self,
-                    priority: Priority,
-                    memory_width: Width,
-                    peripheral_width: Width,
-                    circular: bool,
-                ) {
-                    unsafe {
-                        (*DMA::ptr()).$chXctl.modify(|_, w| {
-                            w.m2m()
-                                .disabled()
-                                .dir()
-                                .from_peripheral()
-                                .prio()
-                                .bits(priority as u8)
-                                .mwidth()
-                                .bits(memory_width as u8)
-                                .pwidth()
-                                .bits(peripheral_width as u8)
-                                .cmen()
-                                .bit(circular)
-                        });
-                    }
-                }
+                        /// Returns `true` if there's a transfer in progress
+                        pub fn in_progress(&self) -> bool {
+                            self.intf().$ftfifX().is_not_complete()
+                        }
 
-                /// Configures the DMA channel to transfer data from memory to a peripheral.
-                #[allow(dead_code)]
-                pub(crate) fn configure_to_peripheral(
-                    &mut self,
-                    priority: Priority,
-                    memory_width: Width,
-                    peripheral_width: Width,
-                    circular: bool,
-                ) {
-                    unsafe {
-                        (*DMA::ptr()).$chXctl.modify(|_, w| {
-                            w.m2m()
-                                .disabled()
-                                .dir()
-                                .from_memory()
-                                .prio()
-                                .bits(priority as u8)
-                                .mwidth()
-                                .bits(memory_width as u8)
-                                .pwidth()
-                                .bits(peripheral_width as u8)
-                                .cmen()
-                                .bit(circular)
-                        });
-                    }
-                }
+                        pub fn listen(&mut self, event: Event) {
+                            match event {
+                                Event::HalfTransfer => unsafe { &(*$DMAX::ptr()).$chXctl }.modify(|_, w| w.htfie().enabled()),
+                                Event::TransferComplete => unsafe { &(*$DMAX::ptr()).$chXctl }.modify(|_, w| w.ftfie().enabled()),
+                            }
+                        }
 
-                fn intf(&self) -> dma::intf::R {
-                    // NOTE(unsafe) atomic read with no side effects
-                    unsafe { (*DMA::ptr()).intf.read() }
-                }
+                        pub fn unlisten(&mut self, event: Event) {
+                            match event {
+                                Event::HalfTransfer => unsafe { &(*$DMAX::ptr()).$chXctl }.modify(|_, w| w.htfie().disabled()),
+                                Event::TransferComplete => unsafe { &(*$DMAX::ptr()).$chXctl }.modify(|_, w| w.ftfie().disabled()),
+                            }
+                        }
 
-                fn intc(&self) -> &dma::INTC {
-                    unsafe { &(*DMA::ptr()).intc }
-                }
+                        /// Configures the DMA channel to transfer data from a peripheral to memory.
+                        #[allow(dead_code)]
+                        pub(crate) fn configure_from_peripheral(
+                            &mut self,
+                            priority: Priority,
+                            memory_width: Width,
+                            peripheral_width: Width,
+                            circular: bool,
+                        ) {
+                            unsafe {
+                                (*$DMAX::ptr()).$chXctl.modify(|_, w| {
+                                    w.m2m()
+                                        .disabled()
+                                        .dir()
+                                        .from_peripheral()
+                                        .prio()
+                                        .bits(priority as u8)
+                                        .mwidth()
+                                        .bits(memory_width as u8)
+                                        .pwidth()
+                                        .bits(peripheral_width as u8)
+                                        .cmen()
+                                        .bit(circular)
+                                });
+                            }
+                        }
 
-                fn get_cnt(&self) -> u16 {
-                    unsafe { &(*DMA::ptr()).$chXcnt }.read().cnt().bits()
-                }
-            }
+                        /// Configures the DMA channel to transfer data from memory to a peripheral.
+                        #[allow(dead_code)]
+                        pub(crate) fn configure_to_peripheral(
+                            &mut self,
+                            priority: Priority,
+                            memory_width: Width,
+                            peripheral_width: Width,
+                            circular: bool,
+                        ) {
+                            unsafe {
+                                (*$DMAX::ptr()).$chXctl.modify(|_, w| {
+                                    w.m2m()
+                                        .disabled()
+                                        .dir()
+                                        .from_memory()
+                                        .prio()
+                                        .bits(priority as u8)
+                                        .mwidth()
+                                        .bits(memory_width as u8)
+                                        .pwidth()
+                                        .bits(peripheral_width as u8)
+                                        .cmen()
+                                        .bit(circular)
+                                });
+                            }
+                        }
 
-            impl<B, PAYLOAD> CircBuffer<B, RxDma<PAYLOAD, $CX>>
-            where
-                RxDma<PAYLOAD, $CX>: TransferPayload,
-            {
-                /// Peeks into the readable half of the buffer
-                pub fn peek<R, F>(&mut self, f: F) -> Result<R, Error>
-                where
-                    F: FnOnce(&B, Half) -> R,
-                {
-                    let half_being_read = self.readable_half()?;
-
-                    let buf = match half_being_read {
-                        Half::First => &self.buffer[0],
-                        Half::Second => &self.buffer[1],
-                    };
-
-                    // XXX does this need a compiler barrier?
-                    let ret = f(buf, half_being_read);
-
-                    let intf = self.payload.channel.intf();
-                    let first_half_is_done = intf.$htfifX().is_half();
-                    let second_half_is_done = intf.$ftfifX().is_complete();
-
-                    if (half_being_read == Half::First && second_half_is_done)
-                        || (half_being_read == Half::Second && first_half_is_done)
-                    {
-                        Err(Error::Overrun)
-                    } else {
-                        Ok(ret)
-                    }
-                }
+                        fn intf(&self) -> dma::intf::R {
+                            // NOTE(unsafe) atomic read with no side effects
+                            unsafe { (*$DMAX::ptr()).intf.read() }
+                        }
 
-                /// Returns the `Half` of the buffer that can be read
-                pub fn readable_half(&mut self) -> Result<Half, Error> {
-                    let intf = self.payload.channel.intf();
-                    let first_half_is_done = intf.$htfifX().is_half();
-                    let second_half_is_done = intf.$ftfifX().is_complete();
+                        fn intc(&self) -> &dma::INTC {
+                            unsafe { &(*$DMAX::ptr()).intc }
+                        }
 
-                    if first_half_is_done && second_half_is_done {
-                        return Err(Error::Overrun);
+                        pub(crate) fn get_cnt(&self) -> u16 {
+                            unsafe { &(*$DMAX::ptr()).$chXcnt }.read().cnt().bits()
+                        }
                     }
 
-                    let last_read_half = self.readable_half;
-
-                    Ok(match last_read_half {
-                        Half::First => {
-                            if second_half_is_done {
-                                self.payload.channel.intc().write(|w| w.$ftfifcX().clear());
-
-                                self.readable_half = Half::Second;
-                                Half::Second
+                    impl<B, PAYLOAD> CircBuffer<B, RxDma<PAYLOAD, $CX>>
+                    where
+                        RxDma<PAYLOAD, $CX>: TransferPayload,
+                    {
+                        /// Peeks into the readable half of the buffer
+                        pub fn peek<R, F>(&mut self, f: F) -> Result<R, Error>
+                        where
+                            F: FnOnce(&B, Half) -> R,
+                        {
+                            let half_being_read = self.readable_half()?;
+
+                            let buf = match half_being_read {
+                                Half::First => &self.buffer[0],
+                                Half::Second => &self.buffer[1],
+                            };
+
+                            // XXX does this need a compiler barrier?
+                            let ret = f(buf, half_being_read);
+
+                            let intf = self.payload.channel.intf();
+                            let first_half_is_done = intf.$htfifX().is_half();
+                            let second_half_is_done = intf.$ftfifX().is_complete();
+
+                            if (half_being_read == Half::First && second_half_is_done)
+                                || (half_being_read == Half::Second && first_half_is_done)
+                            {
+                                Err(Error::Overrun)
                             } else {
-                                last_read_half
+                                Ok(ret)
                             }
                         }
-                        Half::Second => {
-                            if first_half_is_done {
-                                self.payload.channel.intc().write(|w| w.$htfifcX().clear());
 
-                                self.readable_half = Half::First;
-                                Half::First
-                            } else {
-                                last_read_half
+                        /// Returns the `Half` of the buffer that can be read
+                        pub fn readable_half(&mut self) -> Result<Half, Error> {
+                            let intf = self.payload.channel.intf();
+                            let first_half_is_done = intf.$htfifX().is_half();
+                            let second_half_is_done = intf.$ftfifX().is_complete();
+
+                            if first_half_is_done && second_half_is_done {
+                                return Err(Error::Overrun);
                             }
+
+                            let last_read_half = self.readable_half;
+
+                            Ok(match last_read_half {
+                                Half::First => {
+                                    if second_half_is_done {
+                                        self.payload.channel.intc().write(|w| w.$ftfifcX().clear());
+
+                                        self.readable_half = Half::Second;
+                                        Half::Second
+                                    } else {
+                                        last_read_half
+                                    }
+                                }
+                                Half::Second => {
+                                    if first_half_is_done {
+                                        self.payload.channel.intc().write(|w| w.$htfifcX().clear());
+
+                                        self.readable_half = Half::First;
+                                        Half::First
+                                    } else {
+                                        last_read_half
+                                    }
+                                }
+                            })
                         }
-                    })
-                }
 
-                /// Stops the transfer and returns the underlying buffer and RxDma
-                pub fn stop(mut self) -> (&'static mut [B; 2], RxDma<PAYLOAD, $CX>) {
-                    self.payload.stop();
+                        /// Stops the transfer and returns the underlying buffer and RxDma
+                        pub fn stop(mut self) -> (&'static mut [B; 2], RxDma<PAYLOAD, $CX>) {
+                            self.payload.stop();
 
-                    (self.buffer, self.payload)
-                }
-            }
+                            (self.buffer, self.payload)
+                        }
+                    }
 
-            impl<BUFFER, PAYLOAD, MODE> Transfer<MODE, BUFFER, RxDma<PAYLOAD, $CX>>
-            where
-                RxDma<PAYLOAD, $CX>: TransferPayload,
-            {
-                pub fn is_done(&self) -> bool {
-                    !self.payload.channel.in_progress()
-                }
+                    impl<BUFFER, PAYLOAD, MODE> Transfer<MODE, BUFFER, RxDma<PAYLOAD, $CX>>
+                    where
+                        RxDma<PAYLOAD, $CX>: TransferPayload,
+                    {
+                        pub fn is_done(&self) -> bool {
+                            !self.payload.channel.in_progress()
+                        }
 
-                pub fn wait(mut self) -> (BUFFER, RxDma<PAYLOAD, $CX>) {
-                    while !self.is_done() {}
+                        pub fn wait(mut self) -> (BUFFER, RxDma<PAYLOAD, $CX>) {
+                            while !self.is_done() {}
 
-                    atomic::compiler_fence(Ordering::Acquire);
+                            atomic::compiler_fence(Ordering::Acquire);
 
-                    self.payload.stop();
+                            self.payload.stop();
 
-                    // we need a read here to make the Acquire fence effective
-                    // we do *not* need this if `dma.stop` does a RMW operation
-                    unsafe {
-                        ptr::read_volatile(&0);
-                    }
+                            // we need a read here to make the Acquire fence effective
+                            // we do *not* need this if `dma.stop` does a RMW operation
+                            unsafe {
+                                ptr::read_volatile(&0);
+                            }
 
-                    // we need a fence here for the same reason we need one in `Transfer.wait`
-                    atomic::compiler_fence(Ordering::Acquire);
-
-                    // `Transfer` needs to have a `Drop` implementation, because we accept
-                    // managed buffers that can free their memory on drop. Because of that
-                    // we can't move out of the `Transfer`'s fields, so we use `ptr::read`
-                    // and `mem::forget`.
-                    //
-                    // NOTE(unsafe) There is no panic branch between getting the resources
-                    // and forgetting `self`.
-                    unsafe {
-                        let buffer = ptr::read(&self.buffer);
-                        let payload = ptr::read(&self.payload);
-                        mem::forget(self);
-                        (buffer, payload)
+                            // we need a fence here for the same reason we need one in `Transfer.wait`
+                            atomic::compiler_fence(Ordering::Acquire);
+
+                            // `Transfer` needs to have a `Drop` implementation, because we accept
+                            // managed buffers that can free their memory on drop. Because of that
+                            // we can't move out of the `Transfer`'s fields, so we use `ptr::read`
+                            // and `mem::forget`.
+                            //
+                            // NOTE(unsafe) There is no panic branch between getting the resources
+                            // and forgetting `self`.
+                            unsafe {
+                                let buffer = ptr::read(&self.buffer);
+                                let payload = ptr::read(&self.payload);
+                                mem::forget(self);
+                                (buffer, payload)
+                            }
+                        }
                     }
-                }
-            }
 
-            impl<BUFFER, PAYLOAD, MODE> Transfer<MODE, BUFFER, TxDma<PAYLOAD, $CX>>
-            where
-                TxDma<PAYLOAD, $CX>: TransferPayload,
-            {
-                pub fn is_done(&self) -> bool {
-                    !self.payload.channel.in_progress()
-                }
+                    impl<BUFFER, PAYLOAD, MODE> Transfer<MODE, BUFFER, TxDma<PAYLOAD, $CX>>
+                    where
+                        TxDma<PAYLOAD, $CX>: TransferPayload,
+                    {
+                        pub fn is_done(&self) -> bool {
+                            !self.payload.channel.in_progress()
+                        }
 
-                pub fn wait(mut self) -> (BUFFER, TxDma<PAYLOAD, $CX>) {
-                    while !self.is_done() {}
+                        pub fn wait(mut self) -> (BUFFER, TxDma<PAYLOAD, $CX>) {
+                            while !self.is_done() {}
 
-                    atomic::compiler_fence(Ordering::Acquire);
+                            atomic::compiler_fence(Ordering::Acquire);
 
-                    self.payload.stop();
+                            self.payload.stop();
 
-                    // we need a read here to make the Acquire fence effective
-                    // we do *not* need this if `dma.stop` does a RMW operation
-                    unsafe {
-                        ptr::read_volatile(&0);
-                    }
+                            // we need a read here to make the Acquire fence effective
+                            // we do *not* need this if `dma.stop` does a RMW operation
+                            unsafe {
+                                ptr::read_volatile(&0);
+                            }
 
-                    // we need a fence here for the same reason we need one in `Transfer.wait`
-                    atomic::compiler_fence(Ordering::Acquire);
-
-                    // `Transfer` needs to have a `Drop` implementation, because we accept
-                    // managed buffers that can free their memory on drop. Because of that
-                    // we can't move out of the `Transfer`'s fields, so we use `ptr::read`
-                    // and `mem::forget`.
-                    //
-                    // NOTE(unsafe) There is no panic branch between getting the resources
-                    // and forgetting `self`.
-                    unsafe {
-                        let buffer = ptr::read(&self.buffer);
-                        let payload = ptr::read(&self.payload);
-                        mem::forget(self);
-                        (buffer, payload)
+                            // we need a fence here for the same reason we need one in `Transfer.wait`
+                            atomic::compiler_fence(Ordering::Acquire);
+
+                            // `Transfer` needs to have a `Drop` implementation, because we accept
+                            // managed buffers that can free their memory on drop. Because of that
+                            // we can't move out of the `Transfer`'s fields, so we use `ptr::read`
+                            // and `mem::forget`.
+                            //
+                            // NOTE(unsafe) There is no panic branch between getting the resources
+                            // and forgetting `self`.
+                            unsafe {
+                                let buffer = ptr::read(&self.buffer);
+                                let payload = ptr::read(&self.payload);
+                                mem::forget(self);
+                                (buffer, payload)
+                            }
+                        }
                     }
-                }
-            }
 
-            impl<BUFFER, PAYLOAD> Transfer<W, BUFFER, RxDma<PAYLOAD, $CX>>
-            where
-                RxDma<PAYLOAD, $CX>: TransferPayload,
-            {
-                pub fn peek<T>(&self) -> &[T]
-                where
-                    BUFFER: AsRef<[T]>,
-                {
-                    let pending = self.payload.channel.get_cnt() as usize;
+                    impl<BUFFER, PAYLOAD> Transfer<W, BUFFER, RxDma<PAYLOAD, $CX>>
+                    where
+                        RxDma<PAYLOAD, $CX>: TransferPayload,
+                    {
+                        pub fn peek<T>(&self) -> &[T]
+                        where
+                            BUFFER: AsRef<[T]>,
+                        {
+                            let pending = self.payload.channel.get_cnt() as usize;
 
-                    let slice = self.buffer.as_ref();
-                    let capacity = slice.len();
+                            let slice = self.buffer.as_ref();
+                            let capacity = slice.len();
 
-                    &slice[..(capacity - pending)]
-                }
-            }
-        )+
+                            &slice[..(capacity - pending)]
+                        }
+                    }
+                )+
 
-        impl DmaExt for DMA {
-            type Channels = Channels;
+                impl DmaExt for $DMAX {
+                    type Channels = Channels;
 
-            fn split(self, ahb: &mut AHB) -> Channels {
-                DMA::enable(ahb);
+                    fn split(self, ahb: &mut AHB) -> Channels {
+                        $DMAX::enable(ahb);
 
-                // reset the DMA control registers (stops all on-going transfers)
-                $(
-                    self.$chXctl.reset();
-                )+
+                        // reset the DMA control registers (stops all on-going transfers)
+                        $(
+                            self.$chXctl.reset();
+                        )+
 
-                Channels($($CX { _0: () }),+)
+                        Channels($($CX { _0: () }),+)
+                    }
+                }
             }
-        }
+        )+
     }
 }
 
@@ -526,39 +534,68 @@ where
 }
 
 dma! {
-    C0: (
-        htfif0, ftfif0,
-        htfifc0, ftfifc0, gifc0,
-        ch0ctl, ch0cnt, ch0maddr, ch0paddr,
-    ),
-    C1: (
-        htfif1, ftfif1,
-        htfifc1, ftfifc1, gifc1,
-        ch1ctl, ch1cnt, ch1maddr, ch1paddr,
-    ),
-    C2: (
-        htfif2, ftfif2,
-        htfifc2, ftfifc2, gifc2,
-        ch2ctl, ch2cnt, ch2maddr, ch2paddr,
-    ),
-    C3: (
-        htfif3, ftfif3,
-        htfifc3, ftfifc3, gifc3,
-        ch3ctl, ch3cnt, ch3maddr, ch3paddr,
-    ),
-    C4: (
-        htfif4, ftfif4,
-        htfifc4, ftfifc4, gifc4,
-        ch4ctl, ch4cnt, ch4maddr, ch4paddr,
-    ),
-    C5: (
-        htfif5, ftfif5,
-        htfifc5, ftfifc5, gifc5,
-        ch5ctl, ch5cnt, ch5maddr, ch5paddr,
-    ),
-    C6: (
-        htfif6, ftfif6,
-        htfifc6, ftfifc6, gifc6,
-        ch6ctl, ch6cnt, ch6maddr, ch6paddr,
-    ),
+    DMA0: (dma0, {
+        C0: (
+            htfif0, ftfif0,
+            htfifc0, ftfifc0, gifc0,
+            ch0ctl, ch0cnt, ch0maddr, ch0paddr,
+        ),
+        C1: (
+            htfif1, ftfif1,
+            htfifc1, ftfifc1, gifc1,
+            ch1ctl, ch1cnt, ch1maddr, ch1paddr,
+        ),
+        C2: (
+            htfif2, ftfif2,
+            htfifc2, ftfifc2, gifc2,
+            ch2ctl, ch2cnt, ch2maddr, ch2paddr,
+        ),
+        C3: (
+            htfif3, ftfif3,
+            htfifc3, ftfifc3, gifc3,
+            ch3ctl, ch3cnt, ch3maddr, ch3paddr,
+        ),
+        C4: (
+            htfif4, ftfif4,
+            htfifc4, ftfifc4, gifc4,
+            ch4ctl, ch4cnt, ch4maddr, ch4paddr,
+        ),
+        C5: (
+            htfif5, ftfif5,
+            htfifc5, ftfifc5, gifc5,
+            ch5ctl, ch5cnt, ch5maddr, ch5paddr,
+        ),
+        C6: (
+            htfif6, ftfif6,
+            htfifc6, ftfifc6, gifc6,
+            ch6ctl, ch6cnt, ch6maddr, ch6paddr,
+        ),
+    }),
+    DMA1: (dma1, {
+        C0: (
+            htfif0, ftfif0,
+            htfifc0, ftfifc0, gifc0,
+            ch0ctl, ch0cnt, ch0maddr, ch0paddr,
+        ),
+        C1: (
+            htfif1, ftfif1,
+            htfifc1, ftfifc1, gifc1,
+            ch1ctl, ch1cnt, ch1maddr, ch1paddr,
+        ),
+        C2: (
+            htfif2, ftfif2,
+            htfifc2, ftfifc2, gifc2,
+            ch2ctl, ch2cnt, ch2maddr, ch2paddr,
+        ),
+        C3: (
+            htfif3, ftfif3,
+            htfifc3, ftfifc3, gifc3,
+            ch3ctl, ch3cnt, ch3maddr, ch3paddr,
+        ),
+        C4: (
+            htfif4, ftfif4,
+            htfifc4, ftfifc4, gifc4,
+            ch4ctl, ch4cnt, ch4maddr, ch4paddr,
+        ),
+    }),
 }
@@ -506,6 +506,19 @@ impl<const P: char, const N: u8> Pin<P, N, Output<OpenDrain>> {
     }
 }
 
+impl<const P: char, const N: u8> Pin<P, N, Alternate<OpenDrain>> {
+    /// Reads the level on the line, which can be driven low by other devices.
+    #[inline]
+    pub fn is_high(&self) -> bool {
+        !self._is_low()
+    }
+
+    #[inline]
+    pub fn is_low(&self) -> bool {
+        self._is_low()
+    }
+}
+
 impl<const P: char, const N: u8> InputPin for Pin<P, N, Output<OpenDrain>> {
     type Error = Infallible;
 
@@ -3,18 +3,36 @@
 // This document describes a correct I2C implementation and is what parts of this code is based on:
 // https://www.st.com/content/ccc/resource/technical/document/application_note/5d/ae/a3/6f/08/69/4e/9b/CD00209826.pdf/files/CD00209826.pdf/jcr:content/translations/en.CD00209826.pdf
 
+use crate::dma::{
+    dma0, Priority, Receive, RxDma, Transfer, TransferPayload, Transmit, TxDma, Width, R, W,
+};
 use crate::gpio::gpiob::*;
-use crate::gpio::{Alternate, OpenDrain};
-use crate::pac::{DWT, I2C0, I2C1};
+use crate::gpio::{Alternate, OpenDrain, Pin, PinState, HL};
+use crate::pac::{I2C0, I2C1};
 use crate::rcu::{Clocks, Enable, GetBusFreq, Reset, APB1};
-use crate::time::Hertz;
+use crate::time::{Hertz, MicroSeconds};
 use core::ops::Deref;
-use embedded_hal::blocking::i2c::{Read, Write, WriteRead};
+use core::sync::atomic::{self, Ordering};
+use cortex_m::asm::delay;
+use cortex_m::peripheral::{DCB, DWT};
+use embedded_dma::{ReadBuffer, WriteBuffer};
+use embedded_hal::blocking::i2c::{
+    Operation, Read, SevenBitAddress, TenBitAddress, Transactional, Write, WriteIter,
+    WriteIterRead, WriteRead,
+};
 use nb::Error::{Other, WouldBlock};
 use nb::{Error as NbError, Result as NbResult};
 
+mod interrupt;
+mod slave;
+mod timeout;
+
+pub use interrupt::{AsyncI2c, Completion};
+pub use slave::{I2cSlave, MatchedAddress, SlaveAddress, SlaveConfig, SlaveEvent, SmBusDevice};
+pub use timeout::{DwtTimeout, SysTickTimeout, TimeoutSource, TimerTimeout};
+
 /// I2C error
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[non_exhaustive]
 pub enum Error {
     /// Bus error
@@ -25,9 +43,12 @@ pub enum Error {
     Acknowledge,
     /// Overrun/underrun
     Overrun,
-    // Pec, // SMBUS mode only
-    // Timeout, // SMBUS mode only
-    // Alert, // SMBUS mode only
+    /// PEC mismatch, SMBus mode only
+    Pec,
+    /// SMBus block count of zero or more than `SMBUS_BLOCK_MAX`, SMBus mode only
+    BlockCount,
+    /// SCL held low for too long, SMBus mode only
+    Timeout,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -45,6 +66,10 @@ pub enum Mode {
         frequency: Hertz,
         duty_cycle: DutyCycle,
     },
+    FastPlus {
+        frequency: Hertz,
+        duty_cycle: DutyCycle,
+    },
 }
 
 impl Mode {
@@ -61,10 +86,29 @@ impl Mode {
         }
     }
 
+    /// Fast-mode Plus, for frequencies up to 1 MHz. This needs a pclk1 of at least 24 MHz, and the
+    /// `Ratio16to9` duty cycle needs a pclk1 of at least 25 times the frequency.
+    pub fn fast_plus<F: Into<Hertz>>(frequency: F, duty_cycle: DutyCycle) -> Self {
+        Mode::FastPlus {
+            frequency: frequency.into(),
+            duty_cycle,
+        }
+    }
+
     pub fn get_frequency(&self) -> Hertz {
         match *self {
             Mode::Standard { frequency } => frequency,
             Mode::Fast { frequency, .. } => frequency,
+            Mode::FastPlus { frequency, .. } => frequency,
+        }
+    }
+
+    /// Returns the maximum frequency allowed in this mode.
+    fn max_frequency(&self) -> Hertz {
+        match *self {
+            Mode::Standard { .. } => Hertz(100_000),
+            Mode::Fast { .. } => Hertz(400_000),
+            Mode::FastPlus { .. } => Hertz(1_000_000),
         }
     }
 }
@@ -91,14 +135,15 @@ pub struct I2c<I2C, SCLPIN, SDAPIN> {
     sda_pin: SDAPIN,
     mode: Mode,
     pclk1: u32,
+    hclk: u32,
 }
 
 /// embedded-hal compatible blocking I2C implementation
 ///
-/// **NOTE**: Before using blocking I2C, you need to enable the DWT cycle counter using the
-/// [DWT::enable_cycle_counter] method.
-pub struct BlockingI2c<I2C, SCLPIN, SDAPIN> {
+/// Timeouts are measured with a [`TimeoutSource`], by default the DWT cycle counter.
+pub struct BlockingI2c<I2C, SCLPIN, SDAPIN, TIMEOUT = DwtTimeout> {
     nb: I2c<I2C, SCLPIN, SDAPIN>,
+    timeout_source: TIMEOUT,
     start_timeout: u32,
     start_retries: u8,
     addr_timeout: u32,
@@ -127,6 +172,9 @@ macro_rules! i2c_impl {
 
         impl<SCLPIN, SDAPIN> BlockingI2c<$I2Cn, SCLPIN, SDAPIN> {
             /// Creates a blocking I2Cn object on the given pins using the embedded-hal `BlockingI2c` trait.
+            ///
+            /// Timeouts are measured with the DWT cycle counter, which this enables. Use
+            /// [`BlockingI2c::new`] for another [`TimeoutSource`].
             #[allow(clippy::too_many_arguments)]
             pub fn $i2cn(
                 i2c: $I2Cn,
@@ -135,10 +183,12 @@ macro_rules! i2c_impl {
                 mode: Mode,
                 clocks: &Clocks,
                 apb: &mut $APBn,
-                start_timeout_us: u32,
+                dcb: &mut DCB,
+                dwt: &mut DWT,
+                start_timeout: MicroSeconds,
                 start_retries: u8,
-                addr_timeout_us: u32,
-                data_timeout_us: u32,
+                addr_timeout: MicroSeconds,
+                data_timeout: MicroSeconds,
             ) -> Self
             where
                 SCLPIN: SclPin<$I2Cn>,
@@ -151,10 +201,12 @@ macro_rules! i2c_impl {
                     mode,
                     clocks,
                     apb,
-                    start_timeout_us,
+                    dcb,
+                    dwt,
+                    start_timeout,
                     start_retries,
-                    addr_timeout_us,
-                    data_timeout_us,
+                    addr_timeout,
+                    data_timeout,
                 )
             }
         }
@@ -164,25 +216,6 @@ macro_rules! i2c_impl {
 i2c_impl!(I2C0, i2c0, APB1);
 i2c_impl!(I2C1, i2c1, APB1);
 
-/// Generates a blocking I2C instance from a universal I2C object.
-fn blocking_i2c<I2C, SCLPIN, SDAPIN>(
-    i2c: I2c<I2C, SCLPIN, SDAPIN>,
-    clocks: &Clocks,
-    start_timeout_us: u32,
-    start_retries: u8,
-    addr_timeout_us: u32,
-    data_timeout_us: u32,
-) -> BlockingI2c<I2C, SCLPIN, SDAPIN> {
-    let sysclk_mhz = clocks.sysclk().0 / 1_000_000;
-    BlockingI2c {
-        nb: i2c,
-        start_timeout: start_timeout_us * sysclk_mhz,
-        start_retries,
-        addr_timeout: addr_timeout_us * sysclk_mhz,
-        data_timeout: data_timeout_us * sysclk_mhz,
-    }
-}
-
 macro_rules! wait_for_flag {
     ($i2c:expr, $flag:ident) => {{
         let stat0 = $i2c.stat0.read();
@@ -199,6 +232,12 @@ macro_rules! wait_for_flag {
         } else if stat0.ouerr().is_overrun() {
             $i2c.stat0.write(|w| w.ouerr().no_overrun());
             Err(Other(Error::Overrun))
+        } else if stat0.pecerr().is_error() {
+            $i2c.stat0.write(|w| w.pecerr().no_error());
+            Err(Other(Error::Pec))
+        } else if stat0.smbto().is_timeout() {
+            $i2c.stat0.write(|w| w.smbto().no_timeout());
+            Err(Other(Error::Timeout))
         } else if stat0.$flag().bit_is_set() {
             Ok(())
         } else {
@@ -221,16 +260,26 @@ macro_rules! busy_wait {
     }};
 }
 
-macro_rules! busy_wait_cycles {
-    ($nb_expr:expr, $cycles:expr) => {{
-        let started = DWT::cycle_count();
-        let cycles = $cycles;
-        busy_wait!($nb_expr, DWT::cycle_count().wrapping_sub(started) >= cycles)
+macro_rules! busy_wait_timeout {
+    ($timeout_source:expr, $nb_expr:expr, $ticks:expr) => {{
+        let started = $timeout_source.now();
+        let ticks = $ticks;
+        busy_wait!($nb_expr, $timeout_source.elapsed(started) >= ticks)
     }};
 }
 
 pub type I2cRegisterBlock = crate::pac::i2c0::RegisterBlock;
 
+/// Slave address used in master mode
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Address {
+    SevenBit(SevenBitAddress),
+    TenBit(TenBitAddress),
+}
+
+/// The minimum I2CCLK frequency for Fast-mode Plus, from the user manual
+const FAST_PLUS_MIN_PCLK1: u32 = 24_000_000;
+
 impl<I2C, SCLPIN, SDAPIN> I2c<I2C, SCLPIN, SDAPIN>
 where
     I2C: Deref<Target = I2cRegisterBlock> + Enable + Reset,
@@ -250,7 +299,19 @@ where
 
         let pclk1 = I2C::Bus::get_frequency(clocks).0;
 
-        assert!(mode.get_frequency().0 <= 400_000);
+        assert!(mode.get_frequency().0 <= mode.max_frequency().0);
+        if let Mode::FastPlus {
+            frequency,
+            ref duty_cycle,
+        } = mode
+        {
+            // The clock divider can't go below 1, so a slow pclk1 can't reach the frequency
+            let periods = match duty_cycle {
+                DutyCycle::Ratio2to1 => 3,
+                DutyCycle::Ratio16to9 => 25,
+            };
+            assert!(pclk1 >= FAST_PLUS_MIN_PCLK1 && pclk1 >= frequency.0 * periods);
+        }
 
         let mut i2c = I2c {
             i2c,
@@ -258,6 +319,7 @@ where
             sda_pin,
             mode,
             pclk1,
+            hclk: clocks.hclk().0,
         };
         i2c.init();
         i2c
@@ -288,18 +350,25 @@ where
                     .ckcfg
                     .write(|w| w.clkc().bits(((self.pclk1 / (freq.0 * 2)) as u16).max(4)));
             }
-            Mode::Fast { ref duty_cycle, .. } => {
-                self.i2c
-                    .rt
-                    .write(|w| unsafe { w.risetime().bits((pclk1_mhz * 300 / 1000 + 1) as u8) });
+            Mode::Fast { ref duty_cycle, .. } | Mode::FastPlus { ref duty_cycle, .. } => {
+                // The maximum SCL rise time is 300 ns in Fast-mode, and 120 ns in Fast-mode Plus
+                let max_rise_time_ns = match self.mode {
+                    Mode::FastPlus { .. } => 120,
+                    _ => 300,
+                };
+                self.i2c.rt.write(|w| unsafe {
+                    w.risetime()
+                        .bits((pclk1_mhz * max_rise_time_ns / 1000 + 1) as u8)
+                });
 
+                // The divider is rounded up so that the frequency doesn't exceed the one requested
                 self.i2c.ckcfg.write(|w| {
                     let (freq, duty) = match duty_cycle {
                         DutyCycle::Ratio2to1 => {
-                            (((self.pclk1 / (freq.0 * 3)) as u16).max(1), false)
+                            ((self.pclk1.div_ceil(freq.0 * 3) as u16).max(1), false)
                         }
                         DutyCycle::Ratio16to9 => {
-                            (((self.pclk1 / (freq.0 * 25)) as u16).max(1), true)
+                            ((self.pclk1.div_ceil(freq.0 * 25) as u16).max(1), true)
                         }
                     };
 
@@ -308,6 +377,10 @@ where
             }
         };
 
+        self.i2c
+            .fmpcfg
+            .write(|w| w.fmpen().bit(matches!(self.mode, Mode::FastPlus { .. })));
+
         self.i2c.ctl0.modify(|_, w| w.i2cen().enabled());
     }
 
@@ -362,6 +435,63 @@ where
     }
 }
 
+impl<I2C, const SCL: u8, const SDA: u8, CTL>
+    I2c<I2C, Pin<'B', SCL, Alternate<OpenDrain>>, Pin<'B', SDA, Alternate<OpenDrain>>>
+where
+    I2C: Deref<Target = I2cRegisterBlock> + Reset,
+    Pin<'B', SCL, Alternate<OpenDrain>>: HL<Ctl = CTL>,
+    Pin<'B', SDA, Alternate<OpenDrain>>: HL<Ctl = CTL>,
+{
+    /// Recovers a bus where a slave holds SDA low, such as after it was reset in the middle of a
+    /// transfer.
+    ///
+    /// SCL is clocked as a GPIO until the slave releases SDA, for up to nine pulses. A START and
+    /// STOP condition are then generated, and the I2C peripheral is reset and reinitialized.
+    /// Returns `Error::Bus` if SDA is still held low.
+    pub fn recover_bus(&mut self, ctl: &mut CTL, apb: &mut I2C::Bus) -> Result<(), Error> {
+        // A clock of at most 50 kHz, as `delay` waits for at least the given number of CPU cycles
+        let half_period = self.hclk / 100_000;
+        let I2c {
+            i2c,
+            scl_pin,
+            sda_pin,
+            ..
+        } = self;
+
+        i2c.ctl0.modify(|_, w| w.i2cen().disabled());
+
+        scl_pin.as_open_drain_output_with_state(ctl, PinState::High, |scl| {
+            for _ in 0..9 {
+                if sda_pin.is_high() {
+                    break;
+                }
+                scl.set_low();
+                delay(half_period);
+                scl.set_high();
+                delay(half_period);
+            }
+        });
+        let released = sda_pin.is_high();
+
+        // SCL is released while the peripheral is disabled, so pulling SDA low and releasing it
+        // is a START followed by a STOP condition.
+        sda_pin.as_open_drain_output_with_state(ctl, PinState::Low, |sda| {
+            delay(half_period);
+            sda.set_high();
+            delay(half_period);
+        });
+
+        I2C::reset(apb);
+        self.init();
+
+        if released {
+            Ok(())
+        } else {
+            Err(Error::Bus)
+        }
+    }
+}
+
 impl<I2C, SCLPIN, SDAPIN> BlockingI2c<I2C, SCLPIN, SDAPIN>
 where
     I2C: Deref<Target = I2cRegisterBlock> + Enable + Reset,
@@ -375,25 +505,76 @@ where
         mode: Mode,
         clocks: &Clocks,
         apb: &mut I2C::Bus,
-        start_timeout_us: u32,
+        dcb: &mut DCB,
+        dwt: &mut DWT,
+        start_timeout: MicroSeconds,
         start_retries: u8,
-        addr_timeout_us: u32,
-        data_timeout_us: u32,
+        addr_timeout: MicroSeconds,
+        data_timeout: MicroSeconds,
     ) -> Self {
-        blocking_i2c(
+        BlockingI2c::new(
             I2c::<I2C, _, _>::create_internal(i2c, scl_pin, sda_pin, mode, clocks, apb),
-            clocks,
-            start_timeout_us,
+            DwtTimeout::new(dcb, dwt, clocks),
+            start_timeout,
             start_retries,
-            addr_timeout_us,
-            data_timeout_us,
+            addr_timeout,
+            data_timeout,
         )
     }
 }
 
-impl<I2C, SCLPIN, SDAPIN> BlockingI2c<I2C, SCLPIN, SDAPIN>
+impl<I2C, SCLPIN, SDAPIN, TIMEOUT> BlockingI2c<I2C, SCLPIN, SDAPIN, TIMEOUT>
+where
+    TIMEOUT: TimeoutSource,
+{
+    /// Generates a blocking I2C instance from a universal I2C object, measuring its timeouts with
+    /// `timeout_source`.
+    pub fn new(
+        i2c: I2c<I2C, SCLPIN, SDAPIN>,
+        timeout_source: TIMEOUT,
+        start_timeout: MicroSeconds,
+        start_retries: u8,
+        addr_timeout: MicroSeconds,
+        data_timeout: MicroSeconds,
+    ) -> Self {
+        BlockingI2c {
+            nb: i2c,
+            start_timeout: timeout_source.ticks(start_timeout),
+            start_retries,
+            addr_timeout: timeout_source.ticks(addr_timeout),
+            data_timeout: timeout_source.ticks(data_timeout),
+            timeout_source,
+        }
+    }
+
+    /// Releases the universal I2C object and the timeout source
+    pub fn free(self) -> (I2c<I2C, SCLPIN, SDAPIN>, TIMEOUT) {
+        (self.nb, self.timeout_source)
+    }
+}
+
+impl<I2C, const SCL: u8, const SDA: u8, CTL, TIMEOUT>
+    BlockingI2c<
+        I2C,
+        Pin<'B', SCL, Alternate<OpenDrain>>,
+        Pin<'B', SDA, Alternate<OpenDrain>>,
+        TIMEOUT,
+    >
+where
+    I2C: Deref<Target = I2cRegisterBlock> + Reset,
+    Pin<'B', SCL, Alternate<OpenDrain>>: HL<Ctl = CTL>,
+    Pin<'B', SDA, Alternate<OpenDrain>>: HL<Ctl = CTL>,
+{
+    /// Recovers a bus where a slave holds SDA low. See [`I2c::recover_bus`].
+    pub fn recover_bus(&mut self, ctl: &mut CTL, apb: &mut I2C::Bus) -> Result<(), Error> {
+        self.nb.recover_bus(ctl, apb)
+    }
+}
+
+impl<I2C, SCLPIN, SDAPIN, TIMEOUT> BlockingI2c<I2C, SCLPIN, SDAPIN, TIMEOUT>
 where
     I2C: Deref<Target = I2cRegisterBlock>,
+    TIMEOUT: TimeoutSource,
 {
     fn send_start_and_wait(&mut self) -> NbResult<(), Error> {
         // According to http://www.st.com/content/ccc/resource/technical/document/errata_sheet/f5/50/c9/46/56/db/4a/f6/CD00197763.pdf/files/CD00197763.pdf/jcr:content/translations/en.CD00197763.pdf
@@ -402,7 +583,11 @@ where
         let mut last_ret: NbResult<(), Error> = Err(WouldBlock);
         while retries_left > 0 {
             self.nb.send_start();
-            last_ret = busy_wait_cycles!(self.nb.wait_after_sent_start(), self.start_timeout);
+            last_ret = busy_wait_timeout!(
+                self.timeout_source,
+                self.nb.wait_after_sent_start(),
+                self.start_timeout
+            );
             if last_ret.is_err() {
                 self.nb.reset();
             } else {
@@ -416,75 +601,176 @@ where
     fn send_addr_and_wait(&mut self, addr: u8, read: bool) -> NbResult<(), Error> {
         self.nb.i2c.stat0.read();
         self.nb.send_addr(addr, read);
-        let ret = busy_wait_cycles!(wait_for_flag!(self.nb.i2c, addsend), self.addr_timeout);
+        let ret = busy_wait_timeout!(
+            self.timeout_source,
+            wait_for_flag!(self.nb.i2c, addsend),
+            self.addr_timeout
+        );
         if ret == Err(Other(Error::Acknowledge)) {
             self.nb.send_stop();
         }
         ret
     }
 
-    fn write_bytes_and_wait(&mut self, bytes: &[u8]) -> NbResult<(), Error> {
+    fn write_iter_and_wait<B>(&mut self, bytes: B) -> NbResult<(), Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
         self.nb.i2c.stat0.read();
         self.nb.i2c.stat1.read();
 
-        self.nb.i2c.data.write(|w| w.trb().bits(bytes[0]));
-
-        for byte in &bytes[1..] {
-            busy_wait_cycles!(wait_for_flag!(self.nb.i2c, tbe), self.data_timeout)?;
-            self.nb.i2c.data.write(|w| w.trb().bits(*byte));
+        let mut written = false;
+        for byte in bytes {
+            busy_wait_timeout!(
+                self.timeout_source,
+                wait_for_flag!(self.nb.i2c, tbe),
+                self.data_timeout
+            )?;
+            self.nb.i2c.data.write(|w| w.trb().bits(byte));
+            written = true;
+        }
+        if written {
+            busy_wait_timeout!(
+                self.timeout_source,
+                wait_for_flag!(self.nb.i2c, btc),
+                self.data_timeout
+            )?;
         }
-        busy_wait_cycles!(wait_for_flag!(self.nb.i2c, btc), self.data_timeout)?;
 
         Ok(())
     }
 
-    fn write_without_stop(&mut self, addr: u8, bytes: &[u8]) -> NbResult<(), Error> {
-        self.send_start_and_wait()?;
-        self.send_addr_and_wait(addr, false)?;
-
-        let ret = self.write_bytes_and_wait(bytes);
+    /// Sends the 10-bit address header, whose low bit is set for a read transfer.
+    fn send_header_and_wait(&mut self, addr: TenBitAddress, read: bool) -> NbResult<(), Error> {
+        let header = 0xF0 | ((addr >> 7) as u8 & 0x06) | u8::from(read);
+        self.nb.i2c.stat0.read();
+        self.nb.i2c.data.write(|w| w.trb().bits(header));
+        let ret = if read {
+            busy_wait_timeout!(
+                self.timeout_source,
+                wait_for_flag!(self.nb.i2c, addsend),
+                self.addr_timeout
+            )
+        } else {
+            busy_wait_timeout!(
+                self.timeout_source,
+                wait_for_flag!(self.nb.i2c, add10send),
+                self.addr_timeout
+            )
+        };
         if ret == Err(Other(Error::Acknowledge)) {
             self.nb.send_stop();
         }
         ret
     }
-}
 
-impl<I2C, SCLPIN, SDAPIN> Write for BlockingI2c<I2C, SCLPIN, SDAPIN>
-where
-    I2C: Deref<Target = I2cRegisterBlock>,
-{
-    type Error = NbError<Error>;
+    /// Generates a START condition and addresses the slave, leaving ADDSEND set.
+    ///
+    /// A 10-bit address is always sent in full for writing, followed by a repeated START and the
+    /// header for reading if `read` is set.
+    fn send_start_and_address(&mut self, addr: Address, read: bool) -> NbResult<(), Error> {
+        self.send_start_and_wait()?;
+        match addr {
+            Address::SevenBit(addr) => self.send_addr_and_wait(addr, read),
+            Address::TenBit(addr) => {
+                assert!(addr < 0x400);
+                self.send_header_and_wait(addr, false)?;
+                self.nb.i2c.stat0.read();
+                self.nb.i2c.data.write(|w| w.trb().bits(addr as u8));
+                let ret = busy_wait_timeout!(
+                    self.timeout_source,
+                    wait_for_flag!(self.nb.i2c, addsend),
+                    self.addr_timeout
+                );
+                if ret == Err(Other(Error::Acknowledge)) {
+                    self.nb.send_stop();
+                }
+                ret?;
 
-    fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Self::Error> {
-        self.write_without_stop(addr, bytes)?;
-        self.nb.send_stop();
-        busy_wait_cycles!(self.nb.wait_for_stop(), self.data_timeout)?;
+                if read {
+                    self.nb.i2c.stat1.read();
+                    self.send_start_and_wait()?;
+                    self.send_header_and_wait(addr, true)?;
+                }
+                Ok(())
+            }
+        }
+    }
 
-        Ok(())
+    fn write_without_stop(&mut self, addr: Address, bytes: &[u8]) -> NbResult<(), Error> {
+        self.write_iter_without_stop(addr, bytes.iter().copied())
     }
-}
 
-impl<I2C, SCLPIN, SDAPIN> Read for BlockingI2c<I2C, SCLPIN, SDAPIN>
-where
-    I2C: Deref<Target = I2cRegisterBlock>,
-{
-    type Error = NbError<Error>;
+    fn write_iter_without_stop<B>(&mut self, addr: Address, bytes: B) -> NbResult<(), Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        self.send_start_and_address(addr, false)?;
 
-    fn read(&mut self, addr: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
-        match buffer.len() {
+        let ret = self.write_iter_and_wait(bytes);
+        if ret == Err(Other(Error::Acknowledge)) {
+            self.nb.send_stop();
+        }
+        ret
+    }
+
+    /// NACKs the last byte of a reception. When PEC is enabled by [`SmBus`], the last byte is the
+    /// PEC, which the peripheral then checks against the one it calculated.
+    fn nack_last_byte(&self) {
+        self.nb.i2c.ctl0.modify(|r, w| {
+            if r.pecen().is_enabled() {
+                w.pectrans().enabled();
+            }
+            w.acken().nak()
+        });
+    }
+
+    /// Ends a reception with a STOP condition, or with a repeated START if `restart` is set.
+    fn send_end(&mut self, restart: bool) {
+        if restart {
+            self.nb.send_start();
+        } else {
+            self.nb.send_stop();
+        }
+    }
+
+    /// Reads `len` bytes, which must not be zero, into `buffer`, NACKing the last one.
+    ///
+    /// The reception ends with a STOP condition, or with a repeated START if `restart` is set, in
+    /// which case the next transfer doesn't wait for the START to be requested again.
+    fn receive<'b, B>(
+        &mut self,
+        addr: Address,
+        mut buffer: B,
+        len: usize,
+        restart: bool,
+    ) -> NbResult<(), Error>
+    where
+        B: Iterator<Item = &'b mut u8>,
+    {
+        let mut next = || buffer.next().unwrap();
+        match len {
             1 => {
-                self.send_start_and_wait()?;
-                self.send_addr_and_wait(addr, true)?;
-                self.nb.i2c.ctl0.modify(|_, w| w.acken().nak());
+                self.send_start_and_address(addr, true)?;
+                self.nack_last_byte();
                 self.nb.i2c.stat0.read();
                 self.nb.i2c.stat1.read();
-                self.nb.send_stop();
-
-                busy_wait_cycles!(wait_for_flag!(self.nb.i2c, rbne), self.data_timeout)?;
-                buffer[0] = self.nb.i2c.data.read().trb().bits();
-
-                busy_wait_cycles!(self.nb.wait_for_stop(), self.data_timeout)?;
+                self.send_end(restart);
+
+                busy_wait_timeout!(
+                    self.timeout_source,
+                    wait_for_flag!(self.nb.i2c, rbne),
+                    self.data_timeout
+                )?;
+                *next() = self.nb.i2c.data.read().trb().bits();
+
+                if !restart {
+                    busy_wait_timeout!(
+                        self.timeout_source,
+                        self.nb.wait_for_stop(),
+                        self.data_timeout
+                    )?;
+                }
                 self.nb.i2c.ctl0.modify(|_, w| w.acken().ack());
             }
             2 => {
@@ -492,72 +778,937 @@ where
                     .i2c
                     .ctl0
                     .modify(|_, w| w.poap().next().acken().ack());
-                self.send_start_and_wait()?;
-                self.send_addr_and_wait(addr, true)?;
+                self.send_start_and_address(addr, true)?;
                 self.nb.i2c.stat0.read();
                 self.nb.i2c.stat1.read();
-                self.nb.i2c.ctl0.modify(|_, w| w.acken().nak());
-
-                busy_wait_cycles!(wait_for_flag!(self.nb.i2c, btc), self.data_timeout)?;
-                self.nb.send_stop();
-                buffer[0] = self.nb.i2c.data.read().trb().bits();
-                buffer[1] = self.nb.i2c.data.read().trb().bits();
-
-                busy_wait_cycles!(self.nb.wait_for_stop(), self.data_timeout)?;
+                self.nack_last_byte();
+
+                busy_wait_timeout!(
+                    self.timeout_source,
+                    wait_for_flag!(self.nb.i2c, btc),
+                    self.data_timeout
+                )?;
+                self.send_end(restart);
+                *next() = self.nb.i2c.data.read().trb().bits();
+                *next() = self.nb.i2c.data.read().trb().bits();
+
+                if !restart {
+                    busy_wait_timeout!(
+                        self.timeout_source,
+                        self.nb.wait_for_stop(),
+                        self.data_timeout
+                    )?;
+                }
                 self.nb
                     .i2c
                     .ctl0
                     .modify(|_, w| w.poap().current().acken().nak());
                 self.nb.i2c.ctl0.modify(|_, w| w.acken().ack());
             }
-            buffer_len => {
-                self.send_start_and_wait()?;
-                self.send_addr_and_wait(addr, true)?;
+            len => {
+                self.send_start_and_address(addr, true)?;
                 self.nb.i2c.ctl0.modify(|_, w| w.acken().ack());
                 self.nb.i2c.stat0.read();
                 self.nb.i2c.stat1.read();
+                self.receive_acked(buffer, len, restart)?;
+            }
+        }
 
-                let (first_bytes, last_two_bytes) = buffer.split_at_mut(buffer_len - 3);
-                for byte in first_bytes {
-                    busy_wait_cycles!(wait_for_flag!(self.nb.i2c, rbne), self.data_timeout)?;
-                    *byte = self.nb.i2c.data.read().trb().bits();
-                }
+        Ok(())
+    }
 
-                busy_wait_cycles!(wait_for_flag!(self.nb.i2c, btc), self.data_timeout)?;
-                self.nb.i2c.ctl0.modify(|_, w| w.acken().nak());
-                last_two_bytes[0] = self.nb.i2c.data.read().trb().bits();
-                self.nb.send_stop();
-                last_two_bytes[1] = self.nb.i2c.data.read().trb().bits();
-                busy_wait_cycles!(wait_for_flag!(self.nb.i2c, rbne), self.data_timeout)?;
-                last_two_bytes[2] = self.nb.i2c.data.read().trb().bits();
+    /// Reads the remaining `len` bytes, which must not be zero, of a reception which is already
+    /// under way with ACK enabled, and NACKs the last one.
+    ///
+    /// The reception ends with a STOP condition, or with a repeated START if `restart` is set.
+    fn receive_acked<'b, B>(
+        &mut self,
+        mut buffer: B,
+        len: usize,
+        restart: bool,
+    ) -> NbResult<(), Error>
+    where
+        B: Iterator<Item = &'b mut u8>,
+    {
+        let mut next = || buffer.next().unwrap();
+        if len >= 3 {
+            for _ in 0..len - 3 {
+                busy_wait_timeout!(
+                    self.timeout_source,
+                    wait_for_flag!(self.nb.i2c, rbne),
+                    self.data_timeout
+                )?;
+                *next() = self.nb.i2c.data.read().trb().bits();
+            }
 
-                busy_wait_cycles!(self.nb.wait_for_stop(), self.data_timeout)?;
-                self.nb.i2c.ctl0.modify(|_, w| w.acken().ack());
+            busy_wait_timeout!(
+                self.timeout_source,
+                wait_for_flag!(self.nb.i2c, btc),
+                self.data_timeout
+            )?;
+            self.nack_last_byte();
+            *next() = self.nb.i2c.data.read().trb().bits();
+            self.send_end(restart);
+            *next() = self.nb.i2c.data.read().trb().bits();
+        } else {
+            // Too few bytes are left to stop the clock before the last one, so it is NACKed while
+            // being received.
+            if len == 2 {
+                busy_wait_timeout!(
+                    self.timeout_source,
+                    wait_for_flag!(self.nb.i2c, rbne),
+                    self.data_timeout
+                )?;
+                self.nack_last_byte();
+                self.send_end(restart);
+                *next() = self.nb.i2c.data.read().trb().bits();
+            } else {
+                self.nack_last_byte();
+                self.send_end(restart);
             }
         }
-
+        busy_wait_timeout!(
+            self.timeout_source,
+            wait_for_flag!(self.nb.i2c, rbne),
+            self.data_timeout
+        )?;
+        *next() = self.nb.i2c.data.read().trb().bits();
+
+        if !restart {
+            busy_wait_timeout!(
+                self.timeout_source,
+                self.nb.wait_for_stop(),
+                self.data_timeout
+            )?;
+        }
+        self.nb.i2c.ctl0.modify(|_, w| w.acken().ack());
         Ok(())
     }
 }
 
-impl<I2C, SCLPIN, SDAPIN> WriteRead for BlockingI2c<I2C, SCLPIN, SDAPIN>
+impl<I2C, SCLPIN, SDAPIN, TIMEOUT> BlockingI2c<I2C, SCLPIN, SDAPIN, TIMEOUT>
 where
     I2C: Deref<Target = I2cRegisterBlock>,
+    TIMEOUT: TimeoutSource,
 {
-    type Error = NbError<Error>;
+    // The 7-bit operations are also inherent, so that untyped address literals don't become
+    // ambiguous between the 7-bit and 10-bit trait implementations.
 
-    fn write_read(&mut self, addr: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), Self::Error> {
+    pub fn write(&mut self, addr: SevenBitAddress, bytes: &[u8]) -> Result<(), NbError<Error>> {
+        self.write_to(Address::SevenBit(addr), bytes)
+    }
+
+    pub fn read(&mut self, addr: SevenBitAddress, buffer: &mut [u8]) -> Result<(), NbError<Error>> {
+        self.read_from(Address::SevenBit(addr), buffer)
+    }
+
+    pub fn write_read(
+        &mut self,
+        addr: SevenBitAddress,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), NbError<Error>> {
+        self.write_read_from(Address::SevenBit(addr), bytes, buffer)
+    }
+
+    fn write_to(&mut self, addr: Address, bytes: &[u8]) -> NbResult<(), Error> {
+        self.write_without_stop(addr, bytes)?;
+        self.nb.send_stop();
+        busy_wait_timeout!(
+            self.timeout_source,
+            self.nb.wait_for_stop(),
+            self.data_timeout
+        )?;
+
+        Ok(())
+    }
+
+    fn read_from(&mut self, addr: Address, buffer: &mut [u8]) -> NbResult<(), Error> {
+        let len = buffer.len();
+        self.receive(addr, buffer.iter_mut(), len, false)
+    }
+
+    fn write_read_from(
+        &mut self,
+        addr: Address,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> NbResult<(), Error> {
         if !bytes.is_empty() {
             self.write_without_stop(addr, bytes)?;
         }
 
         if !buffer.is_empty() {
-            self.read(addr, buffer)?;
+            self.read_from(addr, buffer)?;
         } else if !bytes.is_empty() {
             self.nb.send_stop();
-            busy_wait_cycles!(self.nb.wait_for_stop(), self.data_timeout)?;
+            busy_wait_timeout!(
+                self.timeout_source,
+                self.nb.wait_for_stop(),
+                self.data_timeout
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn write_iter_to<B>(&mut self, addr: Address, bytes: B) -> NbResult<(), Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        self.write_iter_without_stop(addr, bytes)?;
+        self.nb.send_stop();
+        busy_wait_timeout!(
+            self.timeout_source,
+            self.nb.wait_for_stop(),
+            self.data_timeout
+        )?;
+
+        Ok(())
+    }
+
+    fn write_iter_read_from<B>(
+        &mut self,
+        addr: Address,
+        bytes: B,
+        buffer: &mut [u8],
+    ) -> NbResult<(), Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        let mut bytes = bytes.into_iter().peekable();
+        let write = bytes.peek().is_some();
+        if write {
+            self.write_iter_without_stop(addr, bytes)?;
+        }
+
+        if !buffer.is_empty() {
+            self.read_from(addr, buffer)?;
+        } else if write {
+            self.nb.send_stop();
+            busy_wait_timeout!(
+                self.timeout_source,
+                self.nb.wait_for_stop(),
+                self.data_timeout
+            )?;
         }
 
         Ok(())
     }
+
+    /// Executes the operations as a single transaction. Adjacent operations of the same kind are
+    /// merged into one transfer, and a repeated START separates a write from a read. Empty reads
+    /// are skipped, as at least one byte has to be read after addressing the slave.
+    fn exec_on(&mut self, addr: Address, operations: &mut [Operation]) -> NbResult<(), Error> {
+        fn is_read(operation: &Operation) -> bool {
+            matches!(operation, Operation::Read(_))
+        }
+        fn is_skipped(operation: &Operation) -> bool {
+            matches!(operation, Operation::Read(buffer) if buffer.is_empty())
+        }
+
+        let len = operations.len();
+        let mut start = 0;
+        while let Some(first) = (start..len).find(|&i| !is_skipped(&operations[i])) {
+            let read = is_read(&operations[first]);
+            let end = (first..len)
+                .find(|&i| !is_skipped(&operations[i]) && is_read(&operations[i]) != read)
+                .unwrap_or(len);
+            let last = end == len;
+            let group = &mut operations[first..end];
+
+            if read {
+                let group_len = group
+                    .iter()
+                    .map(|operation| match operation {
+                        Operation::Read(buffer) => buffer.len(),
+                        Operation::Write(_) => 0,
+                    })
+                    .sum();
+                let buffer = group.iter_mut().flat_map(|operation| match operation {
+                    Operation::Read(buffer) => buffer.iter_mut(),
+                    Operation::Write(_) => [].iter_mut(),
+                });
+                self.receive(addr, buffer, group_len, !last)?;
+            } else {
+                let bytes = group.iter().flat_map(|operation| match operation {
+                    Operation::Write(bytes) => bytes.iter().copied(),
+                    Operation::Read(_) => [].iter().copied(),
+                });
+                self.write_iter_without_stop(addr, bytes)?;
+                if last {
+                    self.nb.send_stop();
+                    busy_wait_timeout!(
+                        self.timeout_source,
+                        self.nb.wait_for_stop(),
+                        self.data_timeout
+                    )?;
+                }
+            }
+
+            start = end;
+        }
+
+        Ok(())
+    }
+}
+
+macro_rules! blocking_i2c_traits {
+    ($($Address:ty: $variant:ident,)+) => {
+        $(
+            impl<I2C, SCLPIN, SDAPIN, TIMEOUT> Write<$Address> for BlockingI2c<I2C, SCLPIN, SDAPIN, TIMEOUT>
+            where
+                I2C: Deref<Target = I2cRegisterBlock>,
+                TIMEOUT: TimeoutSource,
+            {
+                type Error = NbError<Error>;
+
+                fn write(&mut self, addr: $Address, bytes: &[u8]) -> Result<(), Self::Error> {
+                    self.write_to(Address::$variant(addr), bytes)
+                }
+            }
+
+            impl<I2C, SCLPIN, SDAPIN, TIMEOUT> Read<$Address> for BlockingI2c<I2C, SCLPIN, SDAPIN, TIMEOUT>
+            where
+                I2C: Deref<Target = I2cRegisterBlock>,
+                TIMEOUT: TimeoutSource,
+            {
+                type Error = NbError<Error>;
+
+                fn read(&mut self, addr: $Address, buffer: &mut [u8]) -> Result<(), Self::Error> {
+                    self.read_from(Address::$variant(addr), buffer)
+                }
+            }
+
+            impl<I2C, SCLPIN, SDAPIN, TIMEOUT> WriteRead<$Address> for BlockingI2c<I2C, SCLPIN, SDAPIN, TIMEOUT>
+            where
+                I2C: Deref<Target = I2cRegisterBlock>,
+                TIMEOUT: TimeoutSource,
+            {
+                type Error = NbError<Error>;
+
+                fn write_read(
+                    &mut self,
+                    addr: $Address,
+                    bytes: &[u8],
+                    buffer: &mut [u8],
+                ) -> Result<(), Self::Error> {
+                    self.write_read_from(Address::$variant(addr), bytes, buffer)
+                }
+            }
+
+            impl<I2C, SCLPIN, SDAPIN, TIMEOUT> WriteIter<$Address> for BlockingI2c<I2C, SCLPIN, SDAPIN, TIMEOUT>
+            where
+                I2C: Deref<Target = I2cRegisterBlock>,
+                TIMEOUT: TimeoutSource,
+            {
+                type Error = NbError<Error>;
+
+                fn write<B>(&mut self, addr: $Address, bytes: B) -> Result<(), Self::Error>
+                where
+                    B: IntoIterator<Item = u8>,
+                {
+                    self.write_iter_to(Address::$variant(addr), bytes)
+                }
+            }
+
+            impl<I2C, SCLPIN, SDAPIN, TIMEOUT> WriteIterRead<$Address> for BlockingI2c<I2C, SCLPIN, SDAPIN, TIMEOUT>
+            where
+                I2C: Deref<Target = I2cRegisterBlock>,
+                TIMEOUT: TimeoutSource,
+            {
+                type Error = NbError<Error>;
+
+                fn write_iter_read<B>(
+                    &mut self,
+                    addr: $Address,
+                    bytes: B,
+                    buffer: &mut [u8],
+                ) -> Result<(), Self::Error>
+                where
+                    B: IntoIterator<Item = u8>,
+                {
+                    self.write_iter_read_from(Address::$variant(addr), bytes, buffer)
+                }
+            }
+
+            impl<I2C, SCLPIN, SDAPIN, TIMEOUT> Transactional<$Address> for BlockingI2c<I2C, SCLPIN, SDAPIN, TIMEOUT>
+            where
+                I2C: Deref<Target = I2cRegisterBlock>,
+                TIMEOUT: TimeoutSource,
+            {
+                type Error = NbError<Error>;
+
+                fn exec<'a>(
+                    &mut self,
+                    addr: $Address,
+                    operations: &mut [Operation<'a>],
+                ) -> Result<(), Self::Error> {
+                    self.exec_on(Address::$variant(addr), operations)
+                }
+            }
+        )+
+    };
+}
+
+blocking_i2c_traits! {
+    SevenBitAddress: SevenBit,
+    TenBitAddress: TenBit,
+}
+
+pub type I2c0TxDma<SCLPIN, SDAPIN, TIMEOUT = DwtTimeout> =
+    TxDma<BlockingI2c<I2C0, SCLPIN, SDAPIN, TIMEOUT>, dma0::C5>;
+pub type I2c0RxDma<SCLPIN, SDAPIN, TIMEOUT = DwtTimeout> =
+    RxDma<BlockingI2c<I2C0, SCLPIN, SDAPIN, TIMEOUT>, dma0::C6>;
+pub type I2c1TxDma<SCLPIN, SDAPIN, TIMEOUT = DwtTimeout> =
+    TxDma<BlockingI2c<I2C1, SCLPIN, SDAPIN, TIMEOUT>, dma0::C3>;
+pub type I2c1RxDma<SCLPIN, SDAPIN, TIMEOUT = DwtTimeout> =
+    RxDma<BlockingI2c<I2C1, SCLPIN, SDAPIN, TIMEOUT>, dma0::C4>;
+
+macro_rules! i2cdma {
+    ($(
+        $I2CX:ty: (
+            $TxDmaX:ident,
+            $RxDmaX:ident,
+            $dmatxch:ty,
+            $dmarxch:ty,
+        ),
+    )+) => {
+        $(
+            impl<SCLPIN, SDAPIN, TIMEOUT> Transmit for $TxDmaX<SCLPIN, SDAPIN, TIMEOUT> {
+                type TxChannel = $dmatxch;
+                type ReceivedWord = u8;
+            }
+
+            impl<SCLPIN, SDAPIN, TIMEOUT> Receive for $RxDmaX<SCLPIN, SDAPIN, TIMEOUT> {
+                type RxChannel = $dmarxch;
+                type TransmittedWord = u8;
+            }
+
+            impl<SCLPIN, SDAPIN, TIMEOUT> TransferPayload for $TxDmaX<SCLPIN, SDAPIN, TIMEOUT>
+            where
+                TIMEOUT: TimeoutSource,
+            {
+                fn start(&mut self) {
+                    self.channel.start();
+                }
+
+                /// Stops the DMA channel and ends the transfer on the bus with a STOP condition,
+                /// once the last byte has been sent.
+                fn stop(&mut self) {
+                    self.channel.stop();
+                    let i2c = &mut self.payload;
+                    let _ = busy_wait_timeout!(i2c.timeout_source, wait_for_flag!(i2c.nb.i2c, btc), i2c.data_timeout);
+                    i2c.nb.i2c.ctl1.modify(|_, w| w.dmaon().disabled());
+                    i2c.nb.send_stop();
+                    let _ = busy_wait_timeout!(i2c.timeout_source, i2c.nb.wait_for_stop(), i2c.data_timeout);
+                }
+            }
+
+            impl<SCLPIN, SDAPIN, TIMEOUT> TransferPayload for $RxDmaX<SCLPIN, SDAPIN, TIMEOUT>
+            where
+                TIMEOUT: TimeoutSource,
+            {
+                fn start(&mut self) {
+                    self.channel.start();
+                }
+
+                /// Stops the DMA channel and ends the transfer on the bus with a STOP condition.
+                /// The last byte has already been NACKed because of DMALST, and for a single byte
+                /// the STOP condition has already been requested.
+                fn stop(&mut self) {
+                    self.channel.stop();
+                    let i2c = &mut self.payload;
+                    if i2c.nb.i2c.ctl1.read().dmalst().is_last() {
+                        i2c.nb.send_stop();
+                    }
+                    let _ = busy_wait_timeout!(i2c.timeout_source, i2c.nb.wait_for_stop(), i2c.data_timeout);
+                    i2c.nb
+                        .i2c
+                        .ctl1
+                        .modify(|_, w| w.dmaon().disabled().dmalst().not_last());
+                    i2c.nb.i2c.ctl0.modify(|_, w| w.acken().ack());
+                }
+            }
+
+            impl<SCLPIN, SDAPIN, TIMEOUT> BlockingI2c<$I2CX, SCLPIN, SDAPIN, TIMEOUT> {
+                /// Uses DMA for master writes.
+                pub fn with_tx_dma(self, channel: $dmatxch) -> $TxDmaX<SCLPIN, SDAPIN, TIMEOUT> {
+                    TxDma {
+                        payload: self,
+                        channel,
+                    }
+                }
+
+                /// Uses DMA for master reads.
+                pub fn with_rx_dma(self, channel: $dmarxch) -> $RxDmaX<SCLPIN, SDAPIN, TIMEOUT> {
+                    RxDma {
+                        payload: self,
+                        channel,
+                    }
+                }
+            }
+
+            impl<SCLPIN, SDAPIN, TIMEOUT> $TxDmaX<SCLPIN, SDAPIN, TIMEOUT>
+            where
+                TIMEOUT: TimeoutSource,
+            {
+                pub fn split(self) -> (BlockingI2c<$I2CX, SCLPIN, SDAPIN, TIMEOUT>, $dmatxch) {
+                    let TxDma { payload, channel } = self;
+                    (payload, channel)
+                }
+
+                /// Addresses the slave `addr` and starts writing `buffer` to it, which must not be
+                /// empty. The transfer ends with a STOP condition once it is waited for.
+                ///
+                /// If the slave can't be addressed, the error is returned along with the buffer.
+                #[allow(clippy::type_complexity)]
+                pub fn write<B>(
+                    mut self,
+                    addr: u8,
+                    buffer: B,
+                ) -> Result<Transfer<R, B, Self>, (NbError<Error>, B, Self)>
+                where
+                    B: ReadBuffer<Word = u8>,
+                {
+                    // NOTE(unsafe) We own the buffer now and we won't call other `&mut` on it
+                    // until the end of the transfer.
+                    let (ptr, len) = unsafe { buffer.read_buffer() };
+                    assert!(len > 0);
+
+                    if let Err(error) = self
+                        .payload
+                        .send_start_and_wait()
+                        .and_then(|()| self.payload.send_addr_and_wait(addr, false))
+                    {
+                        return Err((error, buffer, self));
+                    }
+
+                    self.channel.set_peripheral_address(
+                        unsafe { &(*<$I2CX>::ptr()).data as *const _ as u32 },
+                        false,
+                    );
+                    self.channel.set_memory_address(ptr as u32, true);
+                    self.channel.set_transfer_length(len);
+
+                    atomic::compiler_fence(Ordering::Release);
+
+                    self.channel
+                        .configure_to_peripheral(Priority::Medium, Width::Bits8, Width::Bits8, false);
+                    self.start();
+
+                    let i2c = &self.payload.nb.i2c;
+                    i2c.ctl1.modify(|_, w| w.dmaon().enabled());
+                    // Reading STAT1 after STAT0 clears ADDSEND and releases the bus
+                    i2c.stat0.read();
+                    i2c.stat1.read();
+
+                    Ok(Transfer::r(buffer, self))
+                }
+            }
+
+            impl<SCLPIN, SDAPIN, TIMEOUT> $RxDmaX<SCLPIN, SDAPIN, TIMEOUT>
+            where
+                TIMEOUT: TimeoutSource,
+            {
+                pub fn split(self) -> (BlockingI2c<$I2CX, SCLPIN, SDAPIN, TIMEOUT>, $dmarxch) {
+                    let RxDma { payload, channel } = self;
+                    (payload, channel)
+                }
+
+                /// Addresses the slave `addr` and starts reading from it into `buffer`, which must
+                /// not be empty. The last byte is NACKed, and the transfer ends with a STOP
+                /// condition once it is waited for.
+                ///
+                /// If the slave can't be addressed, the error is returned along with the buffer.
+                #[allow(clippy::type_complexity)]
+                pub fn read<B>(
+                    mut self,
+                    addr: u8,
+                    mut buffer: B,
+                ) -> Result<Transfer<W, B, Self>, (NbError<Error>, B, Self)>
+                where
+                    B: WriteBuffer<Word = u8>,
+                {
+                    // NOTE(unsafe) We own the buffer now and we won't call other `&mut` on it
+                    // until the end of the transfer.
+                    let (ptr, len) = unsafe { buffer.write_buffer() };
+                    assert!(len > 0);
+
+                    if let Err(error) = self
+                        .payload
+                        .send_start_and_wait()
+                        .and_then(|()| self.payload.send_addr_and_wait(addr, true))
+                    {
+                        return Err((error, buffer, self));
+                    }
+
+                    self.channel.set_peripheral_address(
+                        unsafe { &(*<$I2CX>::ptr()).data as *const _ as u32 },
+                        false,
+                    );
+                    self.channel.set_memory_address(ptr as u32, true);
+                    self.channel.set_transfer_length(len);
+
+                    atomic::compiler_fence(Ordering::Release);
+
+                    self.channel
+                        .configure_from_peripheral(Priority::Medium, Width::Bits8, Width::Bits8, false);
+                    self.start();
+
+                    let i2c = &self.payload.nb.i2c;
+                    if len == 1 {
+                        // A single byte is NACKed directly, as DMALST only takes effect after the
+                        // second to last byte.
+                        i2c.ctl0.modify(|_, w| w.acken().nak());
+                        i2c.ctl1.modify(|_, w| w.dmaon().enabled());
+                    } else {
+                        i2c.ctl0.modify(|_, w| w.acken().ack());
+                        i2c.ctl1
+                            .modify(|_, w| w.dmaon().enabled().dmalst().last());
+                    }
+                    // Reading STAT1 after STAT0 clears ADDSEND and releases the bus
+                    i2c.stat0.read();
+                    i2c.stat1.read();
+                    if len == 1 {
+                        // The STOP condition must be requested while the single byte is being
+                        // received, so that it follows the NACK.
+                        self.payload.nb.send_stop();
+                    }
+
+                    Ok(Transfer::w(buffer, self))
+                }
+            }
+        )+
+    }
+}
+
+i2cdma! {
+    I2C0: (
+        I2c0TxDma,
+        I2c0RxDma,
+        dma0::C5,
+        dma0::C6,
+    ),
+    I2C1: (
+        I2c1TxDma,
+        I2c1RxDma,
+        dma0::C3,
+        dma0::C4,
+    ),
+}
+
+impl<MODE, B, I2C, SCLPIN, SDAPIN, TIMEOUT, CH>
+    Transfer<MODE, B, TxDma<BlockingI2c<I2C, SCLPIN, SDAPIN, TIMEOUT>, CH>>
+where
+    I2C: Deref<Target = I2cRegisterBlock>,
+    TIMEOUT: TimeoutSource,
+    TxDma<BlockingI2c<I2C, SCLPIN, SDAPIN, TIMEOUT>, CH>: TransferPayload,
+{
+    /// Returns an error which has stalled the transfer, such as the slave not acknowledging a
+    /// byte. The transfer never completes in that case, and should be dropped to release the bus.
+    pub fn bus_error(&self) -> Option<Error> {
+        bus_error(&self.payload.payload.nb.i2c)
+    }
+}
+
+impl<MODE, B, I2C, SCLPIN, SDAPIN, TIMEOUT, CH>
+    Transfer<MODE, B, RxDma<BlockingI2c<I2C, SCLPIN, SDAPIN, TIMEOUT>, CH>>
+where
+    I2C: Deref<Target = I2cRegisterBlock>,
+    TIMEOUT: TimeoutSource,
+    RxDma<BlockingI2c<I2C, SCLPIN, SDAPIN, TIMEOUT>, CH>: TransferPayload,
+{
+    /// Returns an error which has stalled the transfer. The transfer never completes in that
+    /// case, and should be dropped to release the bus.
+    pub fn bus_error(&self) -> Option<Error> {
+        bus_error(&self.payload.payload.nb.i2c)
+    }
+}
+
+fn bus_error(i2c: &I2cRegisterBlock) -> Option<Error> {
+    let stat0 = i2c.stat0.read();
+    if stat0.berr().is_error() {
+        Some(Error::Bus)
+    } else if stat0.lostarb().is_lost() {
+        Some(Error::Arbitration)
+    } else if stat0.aerr().is_error() {
+        Some(Error::Acknowledge)
+    } else if stat0.ouerr().is_overrun() {
+        Some(Error::Overrun)
+    } else if stat0.smbto().is_timeout() {
+        Some(Error::Timeout)
+    } else {
+        None
+    }
+}
+
+/// Marker trait for possible SMBALERT pins for an I2C module.
+pub trait AlertPin<I2C> {}
+
+/// A filler type for when the SMBALERT pin is not used
+pub struct NoAlert;
+
+impl<I2C> AlertPin<I2C> for NoAlert {}
+impl AlertPin<I2C0> for PB5<Alternate<OpenDrain>> {}
+impl AlertPin<I2C1> for PB12<Alternate<OpenDrain>> {}
+
+/// The maximum number of data bytes in an SMBus block transfer
+pub const SMBUS_BLOCK_MAX: usize = 32;
+/// The address which SMBus hosts read to find out which device is asserting SMBALERT
+pub const SMBUS_ALERT_RESPONSE_ADDRESS: u8 = 0x0C;
+/// The address which SMBus devices respond to during Address Resolution Protocol
+pub const SMBUS_DEVICE_DEFAULT_ADDRESS: u8 = 0x61;
+
+/// I2C peripheral operating as an SMBus host
+///
+/// The PEC is calculated by the peripheral, and sent after written data or checked after read data
+/// when enabled.
+pub struct SmBus<I2C, SCLPIN, SDAPIN, ALERTPIN, TIMEOUT = DwtTimeout> {
+    i2c: BlockingI2c<I2C, SCLPIN, SDAPIN, TIMEOUT>,
+    alert_pin: ALERTPIN,
+    pec: bool,
+}
+
+impl<I2C, SCLPIN, SDAPIN, ALERTPIN, TIMEOUT> SmBus<I2C, SCLPIN, SDAPIN, ALERTPIN, TIMEOUT>
+where
+    I2C: Deref<Target = I2cRegisterBlock>,
+    TIMEOUT: TimeoutSource,
+{
+    /// Switches a blocking I2C master to SMBus host mode. The bus frequency should not be above
+    /// 100 kHz.
+    pub fn new(
+        i2c: BlockingI2c<I2C, SCLPIN, SDAPIN, TIMEOUT>,
+        alert_pin: ALERTPIN,
+        pec: bool,
+    ) -> Self
+    where
+        ALERTPIN: AlertPin<I2C>,
+    {
+        i2c.nb
+            .i2c
+            .ctl0
+            .modify(|_, w| w.smben().smbus().smbsel().host().pecen().bit(pec));
+        SmBus {
+            i2c,
+            alert_pin,
+            pec,
+        }
+    }
+
+    /// Switches back to I2C mode, and releases the blocking I2C master and SMBALERT pin
+    pub fn free(self) -> (BlockingI2c<I2C, SCLPIN, SDAPIN, TIMEOUT>, ALERTPIN) {
+        self.i2c
+            .nb
+            .i2c
+            .ctl0
+            .modify(|_, w| w.smben().i2c().pecen().disabled());
+        (self.i2c, self.alert_pin)
+    }
+
+    /// Sends a single byte without a command code.
+    pub fn send_byte(&mut self, addr: u8, byte: u8) -> Result<(), NbError<Error>> {
+        self.transmit(addr, &[byte])
+    }
+
+    /// Receives a single byte without a command code.
+    pub fn receive_byte(&mut self, addr: u8) -> Result<u8, NbError<Error>> {
+        let mut buffer = [0];
+        self.receive(addr, None, &mut buffer)?;
+        Ok(buffer[0])
+    }
+
+    pub fn write_byte(&mut self, addr: u8, command: u8, byte: u8) -> Result<(), NbError<Error>> {
+        self.transmit(addr, &[command, byte])
+    }
+
+    pub fn read_byte(&mut self, addr: u8, command: u8) -> Result<u8, NbError<Error>> {
+        let mut buffer = [0];
+        self.receive(addr, Some(command), &mut buffer)?;
+        Ok(buffer[0])
+    }
+
+    pub fn write_word(&mut self, addr: u8, command: u8, word: u16) -> Result<(), NbError<Error>> {
+        let [low, high] = word.to_le_bytes();
+        self.transmit(addr, &[command, low, high])
+    }
+
+    pub fn read_word(&mut self, addr: u8, command: u8) -> Result<u16, NbError<Error>> {
+        let mut buffer = [0; 2];
+        self.receive(addr, Some(command), &mut buffer)?;
+        Ok(u16::from_le_bytes(buffer))
+    }
+
+    /// Writes up to `SMBUS_BLOCK_MAX` bytes, preceded by their count.
+    pub fn write_block(
+        &mut self,
+        addr: u8,
+        command: u8,
+        data: &[u8],
+    ) -> Result<(), NbError<Error>> {
+        assert!(data.len() <= SMBUS_BLOCK_MAX);
+        let mut buffer = [0; SMBUS_BLOCK_MAX + 2];
+        buffer[0] = command;
+        buffer[1] = data.len() as u8;
+        buffer[2..2 + data.len()].copy_from_slice(data);
+        self.transmit(addr, &buffer[..2 + data.len()])
+    }
+
+    /// Reads a block, whose length is given by the device in the first byte it sends.
+    pub fn read_block<'a>(
+        &mut self,
+        addr: u8,
+        command: u8,
+        buffer: &'a mut [u8; SMBUS_BLOCK_MAX],
+    ) -> Result<&'a [u8], NbError<Error>> {
+        let count = self.receive_block(addr, command, buffer)?;
+        Ok(&buffer[..count])
+    }
+
+    /// Returns the address of a device asserting SMBALERT, by reading from the alert response
+    /// address. Returns `None` if no alert is pending.
+    pub fn alert_response(&mut self) -> Result<Option<u8>, NbError<Error>> {
+        let i2c = &self.i2c.nb.i2c;
+        if i2c.stat0.read().smbalt().is_no_alert() {
+            return Ok(None);
+        }
+        i2c.stat0.write(|w| w.smbalt().no_alert());
+
+        let mut buffer = [0];
+        self.receive(SMBUS_ALERT_RESPONSE_ADDRESS, None, &mut buffer)?;
+        Ok(Some(buffer[0] >> 1))
+    }
+
+    /// Restarts the PEC calculation for a new transaction.
+    fn reset_pec(&mut self) {
+        if self.pec {
+            let i2c = &self.i2c.nb.i2c;
+            i2c.ctl0.modify(|_, w| w.pecen().disabled());
+            i2c.ctl0.modify(|_, w| w.pecen().enabled());
+        }
+    }
+
+    /// Writes `bytes`, which must not be empty, followed by the PEC if enabled.
+    fn transmit(&mut self, addr: u8, bytes: &[u8]) -> Result<(), NbError<Error>> {
+        self.reset_pec();
+        if self.pec {
+            // The peripheral sends the PEC once the last byte has been sent, if PECTRANS is set
+            // after writing it.
+            let (&last, bytes) = bytes.split_last().unwrap();
+            self.i2c
+                .write_without_stop(Address::SevenBit(addr), bytes)?;
+            let i2c = &mut self.i2c;
+            let ret = busy_wait_timeout!(
+                i2c.timeout_source,
+                wait_for_flag!(i2c.nb.i2c, tbe),
+                i2c.data_timeout
+            )
+            .and_then(|()| {
+                i2c.nb.i2c.data.write(|w| w.trb().bits(last));
+                i2c.nb.i2c.ctl0.modify(|_, w| w.pectrans().enabled());
+                busy_wait_timeout!(
+                    i2c.timeout_source,
+                    wait_for_flag!(i2c.nb.i2c, btc),
+                    i2c.data_timeout
+                )
+            });
+            if ret == Err(Other(Error::Acknowledge)) {
+                self.i2c.nb.send_stop();
+            }
+            ret?;
+        } else {
+            self.i2c
+                .write_without_stop(Address::SevenBit(addr), bytes)?;
+        }
+        self.i2c.nb.send_stop();
+        busy_wait_timeout!(
+            self.i2c.timeout_source,
+            self.i2c.nb.wait_for_stop(),
+            self.i2c.data_timeout
+        )?;
+
+        Ok(())
+    }
+
+    /// Fills `buffer` from the device, after writing `command` if given.
+    fn receive(
+        &mut self,
+        addr: u8,
+        command: Option<u8>,
+        buffer: &mut [u8],
+    ) -> Result<(), NbError<Error>> {
+        let len = buffer.len() + usize::from(self.pec);
+        let mut received = [0; SMBUS_BLOCK_MAX + 2];
+
+        self.reset_pec();
+        if let Some(command) = command {
+            self.i2c
+                .write_without_stop(Address::SevenBit(addr), &[command])?;
+        }
+        self.i2c.receive(
+            Address::SevenBit(addr),
+            received[..len].iter_mut(),
+            len,
+            false,
+        )?;
+        self.check_pec()?;
+
+        buffer.copy_from_slice(&received[..buffer.len()]);
+        Ok(())
+    }
+
+    /// Reads a block from the device into `buffer`, returning its length.
+    fn receive_block(
+        &mut self,
+        addr: u8,
+        command: u8,
+        buffer: &mut [u8; SMBUS_BLOCK_MAX],
+    ) -> Result<usize, NbError<Error>> {
+        let mut received = [0; SMBUS_BLOCK_MAX + 1];
+
+        self.reset_pec();
+        self.i2c
+            .write_without_stop(Address::SevenBit(addr), &[command])?;
+        self.i2c
+            .send_start_and_address(Address::SevenBit(addr), true)?;
+        let i2c = &self.i2c.nb.i2c;
+        i2c.ctl0.modify(|_, w| w.acken().ack());
+        // Reading STAT1 after STAT0 clears ADDSEND and releases the bus
+        i2c.stat0.read();
+        i2c.stat1.read();
+
+        // The count is received before the rest of the length is known.
+        busy_wait_timeout!(
+            self.i2c.timeout_source,
+            wait_for_flag!(self.i2c.nb.i2c, rbne),
+            self.i2c.data_timeout
+        )?;
+        let count = usize::from(self.i2c.nb.i2c.data.read().trb().bits());
+        if !(1..=SMBUS_BLOCK_MAX).contains(&count) {
+            // End the reception after one more byte, which would be compared as the PEC.
+            let mut discarded = [0];
+            let _ = self.i2c.receive_acked(discarded.iter_mut(), 1, false);
+            self.i2c.nb.i2c.stat0.write(|w| w.pecerr().no_error());
+            return Err(Other(Error::BlockCount));
+        }
+        let len = count + usize::from(self.pec);
+        self.i2c
+            .receive_acked(received[..len].iter_mut(), len, false)?;
+        self.check_pec()?;
+
+        buffer[..count].copy_from_slice(&received[..count]);
+        Ok(count)
+    }
+
+    /// Checks the PEC of a completed reception, which the peripheral compared with the one it
+    /// calculated.
+    fn check_pec(&self) -> Result<(), NbError<Error>> {
+        let i2c = &self.i2c.nb.i2c;
+        if self.pec && i2c.stat0.read().pecerr().is_error() {
+            i2c.stat0.write(|w| w.pecerr().no_error());
+            Err(Other(Error::Pec))
+        } else {
+            Ok(())
+        }
+    }
 }
@@ -0,0 +1,285 @@
+//! Interrupt-driven non-blocking I2C master
+
+use super::{Error, I2c, I2cRegisterBlock};
+use core::ops::Deref;
+
+/// The buffers and result of a completed transaction
+pub struct Completion {
+    pub result: Result<(), Error>,
+    pub write: &'static [u8],
+    pub read: &'static mut [u8],
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum State {
+    Idle,
+    /// Waiting for the START condition to be sent
+    Start,
+    /// Waiting for the address to be acknowledged
+    Address {
+        read: bool,
+    },
+    Write,
+    Read,
+    Done(Result<(), Error>),
+}
+
+/// I2C master whose transactions are advanced by the I2C event and error interrupts
+///
+/// A transaction is started with [`AsyncI2c::write`], [`AsyncI2c::read`] or
+/// [`AsyncI2c::write_read`], after which [`AsyncI2c::on_interrupt`] must be called from both the
+/// event and error interrupt handlers. [`AsyncI2c::poll`] returns the buffers once it completes.
+pub struct AsyncI2c<I2C, SCLPIN, SDAPIN> {
+    nb: I2c<I2C, SCLPIN, SDAPIN>,
+    state: State,
+    addr: u8,
+    write: &'static [u8],
+    written: usize,
+    read: &'static mut [u8],
+    received: usize,
+}
+
+impl<I2C, SCLPIN, SDAPIN> AsyncI2c<I2C, SCLPIN, SDAPIN>
+where
+    I2C: Deref<Target = I2cRegisterBlock>,
+{
+    pub fn new(i2c: I2c<I2C, SCLPIN, SDAPIN>) -> Self {
+        AsyncI2c {
+            nb: i2c,
+            state: State::Idle,
+            addr: 0,
+            write: &[],
+            written: 0,
+            read: &mut [],
+            received: 0,
+        }
+    }
+
+    /// Releases the I2C master. Any transaction in progress is abandoned.
+    pub fn free(mut self) -> I2c<I2C, SCLPIN, SDAPIN> {
+        self.disable_interrupts();
+        self.nb
+    }
+
+    /// Starts writing `bytes` to the slave `addr`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the previous transaction has not been completed and polled.
+    pub fn write(&mut self, addr: u8, bytes: &'static [u8]) {
+        self.start(addr, bytes, &mut []);
+    }
+
+    /// Starts reading from the slave `addr` into `buffer`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the previous transaction has not been completed and polled.
+    pub fn read(&mut self, addr: u8, buffer: &'static mut [u8]) {
+        self.start(addr, &[], buffer);
+    }
+
+    /// Starts writing `bytes` to the slave `addr`, then reading into `buffer` after a repeated
+    /// START.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the previous transaction has not been completed and polled.
+    pub fn write_read(&mut self, addr: u8, bytes: &'static [u8], buffer: &'static mut [u8]) {
+        self.start(addr, bytes, buffer);
+    }
+
+    /// Returns whether a transaction has been started and not yet polled.
+    pub fn is_busy(&self) -> bool {
+        self.state != State::Idle
+    }
+
+    /// Returns the result and buffers of the transaction once it has completed, or `None` while
+    /// it is in progress or if there is none.
+    pub fn poll(&mut self) -> Option<Completion> {
+        match self.state {
+            State::Done(result) => {
+                self.state = State::Idle;
+                Some(Completion {
+                    result,
+                    write: core::mem::take(&mut self.write),
+                    read: core::mem::take(&mut self.read),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Advances the transaction. This must be called from both the event and the error interrupt
+    /// handlers of the I2C peripheral.
+    pub fn on_interrupt(&mut self) {
+        let i2c = &self.nb.i2c;
+        let stat0 = i2c.stat0.read();
+
+        let error = if stat0.berr().is_error() {
+            Some(Error::Bus)
+        } else if stat0.lostarb().is_lost() {
+            Some(Error::Arbitration)
+        } else if stat0.aerr().is_error() {
+            Some(Error::Acknowledge)
+        } else if stat0.ouerr().is_overrun() {
+            Some(Error::Overrun)
+        } else {
+            None
+        };
+        if let Some(error) = error {
+            i2c.stat0.write(|w| {
+                w.berr()
+                    .no_error()
+                    .lostarb()
+                    .no_lost()
+                    .aerr()
+                    .no_error()
+                    .ouerr()
+                    .no_overrun()
+            });
+            // After losing arbitration the peripheral is no longer master
+            if error != Error::Arbitration {
+                self.nb.send_stop();
+            }
+            self.finish(Err(error));
+            return;
+        }
+
+        match self.state {
+            State::Start => {
+                if stat0.sbsend().bit_is_set() {
+                    let read = self.written == self.write.len();
+                    // Writing DATA after reading STAT0 clears SBSEND
+                    self.nb.send_addr(self.addr, read);
+                    self.state = State::Address { read };
+                }
+            }
+            State::Address { read } => {
+                if stat0.addsend().bit_is_clear() {
+                    return;
+                }
+                if !read {
+                    // Reading STAT1 after STAT0 clears ADDSEND
+                    i2c.stat1.read();
+                    self.state = State::Write;
+                    return;
+                }
+                match self.read.len() {
+                    1 => {
+                        i2c.ctl0.modify(|_, w| w.acken().nak());
+                        i2c.stat1.read();
+                        self.nb.send_stop();
+                    }
+                    2 => {
+                        i2c.ctl0.modify(|_, w| w.acken().nak().poap().next());
+                        i2c.stat1.read();
+                        i2c.ctl1.modify(|_, w| w.bufie().disabled());
+                    }
+                    len => {
+                        i2c.ctl0.modify(|_, w| w.acken().ack());
+                        i2c.stat1.read();
+                        if len == 3 {
+                            i2c.ctl1.modify(|_, w| w.bufie().disabled());
+                        }
+                    }
+                }
+                self.state = State::Read;
+            }
+            State::Write => {
+                if self.written < self.write.len() {
+                    if stat0.tbe().bit_is_set() {
+                        let byte = self.write[self.written];
+                        i2c.data.write(|w| w.trb().bits(byte));
+                        self.written += 1;
+                        if self.written == self.write.len() {
+                            // Only the byte transfer complete event is needed from now on
+                            i2c.ctl1.modify(|_, w| w.bufie().disabled());
+                        }
+                    }
+                } else if stat0.btc().bit_is_set() {
+                    if self.read.is_empty() {
+                        self.nb.send_stop();
+                        self.finish(Ok(()));
+                    } else {
+                        i2c.ctl1.modify(|_, w| w.bufie().enabled());
+                        self.nb.send_start();
+                        self.state = State::Start;
+                    }
+                }
+            }
+            State::Read => match self.read.len() - self.received {
+                // A single byte, which is NACKed and followed by STOP already
+                1 => {
+                    if stat0.rbne().bit_is_set() {
+                        self.read[self.received] = i2c.data.read().trb().bits();
+                        self.finish(Ok(()));
+                    }
+                }
+                // The second to last byte is in DATA and the last one in the shift register
+                2 => {
+                    if stat0.btc().bit_is_set() {
+                        self.nb.send_stop();
+                        self.read[self.received] = i2c.data.read().trb().bits();
+                        self.read[self.received + 1] = i2c.data.read().trb().bits();
+                        self.finish(Ok(()));
+                    }
+                }
+                3 => {
+                    if stat0.btc().bit_is_set() {
+                        i2c.ctl0.modify(|_, w| w.acken().nak());
+                        self.read[self.received] = i2c.data.read().trb().bits();
+                        self.received += 1;
+                    }
+                }
+                remaining => {
+                    if stat0.rbne().bit_is_set() {
+                        self.read[self.received] = i2c.data.read().trb().bits();
+                        self.received += 1;
+                        if remaining == 4 {
+                            // The last three bytes are read on byte transfer complete events
+                            i2c.ctl1.modify(|_, w| w.bufie().disabled());
+                        }
+                    }
+                }
+            },
+            State::Idle | State::Done(_) => {}
+        }
+    }
+
+    fn start(&mut self, addr: u8, write: &'static [u8], read: &'static mut [u8]) {
+        assert!(!self.is_busy());
+        assert!(!write.is_empty() || !read.is_empty());
+
+        self.addr = addr;
+        self.write = write;
+        self.written = 0;
+        self.read = read;
+        self.received = 0;
+        self.state = State::Start;
+
+        let i2c = &self.nb.i2c;
+        // The STOP condition of the previous transaction may still be pending
+        while i2c.ctl0.read().stop().is_stop() {}
+        i2c.ctl0.modify(|_, w| w.acken().ack());
+        i2c.ctl1
+            .modify(|_, w| w.evie().enabled().bufie().enabled().errie().enabled());
+        self.nb.send_start();
+    }
+
+    fn finish(&mut self, result: Result<(), Error>) {
+        self.disable_interrupts();
+        self.nb
+            .i2c
+            .ctl0
+            .modify(|_, w| w.acken().ack().poap().current());
+        self.state = State::Done(result);
+    }
+
+    fn disable_interrupts(&mut self) {
+        self.nb
+            .i2c
+            .ctl1
+            .modify(|_, w| w.evie().disabled().bufie().disabled().errie().disabled());
+    }
+}
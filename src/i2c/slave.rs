@@ -0,0 +1,296 @@
+//! I2C slave mode with address matching and an event-driven API
+
+use super::{Error, I2cRegisterBlock, SclPin, SdaPin};
+use crate::pac::{I2C0, I2C1};
+use crate::rcu::{Clocks, Enable, GetBusFreq, Reset, APB1};
+use core::ops::Deref;
+use nb::Error::{Other, WouldBlock};
+
+/// Own address of an I2C slave
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SlaveAddress {
+    /// 7-bit address
+    SevenBit(u8),
+    /// 10-bit address
+    TenBit(u16),
+}
+
+/// I2C slave configuration
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SlaveConfig {
+    pub address: SlaveAddress,
+    /// Second 7-bit address to respond to, in addition to `address`
+    pub dual_address: Option<u8>,
+    /// Respond to the general call address 0x00
+    pub general_call: bool,
+    /// Hold SCL low while waiting for the application to read or write data
+    pub clock_stretching: bool,
+    /// Operate as an SMBus device instead of an I2C slave
+    pub smbus: Option<SmBusDevice>,
+}
+
+/// SMBus device options
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SmBusDevice {
+    /// Calculate the PEC, which is checked or sent after [`I2cSlave::send_pec`]
+    pub pec: bool,
+    /// Respond to the Address Resolution Protocol default device address
+    pub arp: bool,
+}
+
+impl SlaveConfig {
+    /// Responds to the given 7-bit address.
+    pub fn seven_bit(address: u8) -> Self {
+        assert!(address < 0x80);
+        Self::new(SlaveAddress::SevenBit(address))
+    }
+
+    /// Responds to the given 10-bit address.
+    pub fn ten_bit(address: u16) -> Self {
+        assert!(address < 0x400);
+        Self::new(SlaveAddress::TenBit(address))
+    }
+
+    fn new(address: SlaveAddress) -> Self {
+        SlaveConfig {
+            address,
+            dual_address: None,
+            general_call: false,
+            clock_stretching: true,
+            smbus: None,
+        }
+    }
+
+    pub fn dual_address(mut self, address: u8) -> Self {
+        assert!(address < 0x80);
+        self.dual_address = Some(address);
+        self
+    }
+
+    pub fn general_call(mut self, general_call: bool) -> Self {
+        self.general_call = general_call;
+        self
+    }
+
+    pub fn clock_stretching(mut self, clock_stretching: bool) -> Self {
+        self.clock_stretching = clock_stretching;
+        self
+    }
+
+    pub fn smbus(mut self, pec: bool, arp: bool) -> Self {
+        self.smbus = Some(SmBusDevice { pec, arp });
+        self
+    }
+}
+
+/// The address a master addressed the slave with
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MatchedAddress {
+    /// The own address
+    Primary,
+    /// The dual address
+    Secondary,
+    /// The general call address
+    GeneralCall,
+    /// The SMBus device default address
+    SmBusDefault,
+}
+
+/// Event on the bus that the slave application must handle
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SlaveEvent {
+    /// The slave has been addressed. If `read` is set, the master reads from the slave.
+    AddressMatched { read: bool, address: MatchedAddress },
+    /// The master has written a byte.
+    ByteReceived(u8),
+    /// The master is reading, and the next byte must be provided with [`I2cSlave::write`].
+    ByteRequested,
+    /// The transfer has ended, either with a STOP condition or with the master not acknowledging
+    /// the last byte it read.
+    Stop,
+}
+
+/// I2C peripheral operating in slave mode
+pub struct I2cSlave<I2C, SCLPIN, SDAPIN> {
+    i2c: I2C,
+    scl_pin: SCLPIN,
+    sda_pin: SDAPIN,
+}
+
+macro_rules! i2c_slave_impl {
+    ($I2Cn:ty, $i2cn:ident, $APBn:ty) => {
+        impl<SCLPIN, SDAPIN> I2cSlave<$I2Cn, SCLPIN, SDAPIN> {
+            /// Creates an I2Cn slave on the given pins.
+            pub fn $i2cn(
+                i2c: $I2Cn,
+                scl_pin: SCLPIN,
+                sda_pin: SDAPIN,
+                config: SlaveConfig,
+                clocks: &Clocks,
+                apb: &mut $APBn,
+            ) -> Self
+            where
+                SCLPIN: SclPin<$I2Cn>,
+                SDAPIN: SdaPin<$I2Cn>,
+            {
+                I2cSlave::<$I2Cn, _, _>::create_internal(i2c, scl_pin, sda_pin, config, clocks, apb)
+            }
+        }
+    };
+}
+
+i2c_slave_impl!(I2C0, i2c0, APB1);
+i2c_slave_impl!(I2C1, i2c1, APB1);
+
+impl<I2C, SCLPIN, SDAPIN> I2cSlave<I2C, SCLPIN, SDAPIN>
+where
+    I2C: Deref<Target = I2cRegisterBlock> + Enable + Reset,
+    I2C::Bus: GetBusFreq,
+{
+    fn create_internal(
+        i2c: I2C,
+        scl_pin: SCLPIN,
+        sda_pin: SDAPIN,
+        config: SlaveConfig,
+        clocks: &Clocks,
+        apb: &mut I2C::Bus,
+    ) -> Self {
+        I2C::enable(apb);
+        I2C::reset(apb);
+
+        let pclk1_mhz = I2C::Bus::get_frequency(clocks).0 / 1_000_000;
+        assert!(pclk1_mhz >= 2);
+
+        i2c.ctl1
+            .write(|w| unsafe { w.i2cclk().bits(pclk1_mhz as u8) });
+        match config.address {
+            SlaveAddress::SevenBit(address) => i2c
+                .saddr0
+                .write(|w| w.address().bits(u16::from(address) << 1).addformat().add7()),
+            SlaveAddress::TenBit(address) => i2c
+                .saddr0
+                .write(|w| w.address().bits(address).addformat().add10()),
+        }
+        match config.dual_address {
+            Some(address) => i2c
+                .saddr1
+                .write(|w| w.address2().bits(address).duaden().dual()),
+            None => i2c.saddr1.write(|w| w.duaden().single()),
+        }
+        i2c.ctl0.write(|w| {
+            if let Some(SmBusDevice { pec, arp }) = config.smbus {
+                w.smben().smbus().smbsel().device();
+                w.pecen().bit(pec).arpen().bit(arp);
+            }
+            w.gcen().bit(config.general_call);
+            w.ss().bit(!config.clock_stretching);
+            w.i2cen().enabled()
+        });
+        // ACKEN can only be set once the peripheral is enabled
+        i2c.ctl0.modify(|_, w| w.acken().ack());
+
+        I2cSlave {
+            i2c,
+            scl_pin,
+            sda_pin,
+        }
+    }
+}
+
+impl<I2C, SCLPIN, SDAPIN> I2cSlave<I2C, SCLPIN, SDAPIN>
+where
+    I2C: Deref<Target = I2cRegisterBlock>,
+{
+    /// Enables the event, buffer and error interrupts, so that [`I2cSlave::event`] can be called
+    /// from the interrupt handler.
+    pub fn listen(&mut self) {
+        self.i2c
+            .ctl1
+            .modify(|_, w| w.evie().enabled().bufie().enabled().errie().enabled());
+    }
+
+    /// Disables the event, buffer and error interrupts.
+    pub fn unlisten(&mut self) {
+        self.i2c
+            .ctl1
+            .modify(|_, w| w.evie().disabled().bufie().disabled().errie().disabled());
+    }
+
+    /// Returns the next pending bus event, or `WouldBlock` if there is none.
+    ///
+    /// With clock stretching enabled, the bus is held until a `ByteReceived` is returned or a
+    /// `ByteRequested` is answered with [`I2cSlave::write`].
+    pub fn event(&mut self) -> nb::Result<SlaveEvent, Error> {
+        let stat0 = self.i2c.stat0.read();
+
+        if stat0.berr().is_error() {
+            self.i2c.stat0.write(|w| w.berr().no_error());
+            Err(Other(Error::Bus))
+        } else if stat0.lostarb().is_lost() {
+            self.i2c.stat0.write(|w| w.lostarb().no_lost());
+            Err(Other(Error::Arbitration))
+        } else if stat0.ouerr().is_overrun() {
+            self.i2c.stat0.write(|w| w.ouerr().no_overrun());
+            Err(Other(Error::Overrun))
+        } else if stat0.pecerr().is_error() {
+            self.i2c.stat0.write(|w| w.pecerr().no_error());
+            Err(Other(Error::Pec))
+        } else if stat0.smbto().is_timeout() {
+            self.i2c.stat0.write(|w| w.smbto().no_timeout());
+            Err(Other(Error::Timeout))
+        } else if stat0.aerr().is_error() {
+            // The master doesn't acknowledge the last byte it reads
+            self.i2c.stat0.write(|w| w.aerr().no_error());
+            Ok(SlaveEvent::Stop)
+        } else if stat0.addsend().bit_is_set() {
+            // Reading STAT1 after STAT0 clears ADDSEND
+            let stat1 = self.i2c.stat1.read();
+            let address = if stat1.rxgc().is_received() {
+                MatchedAddress::GeneralCall
+            } else if stat1.defsmb().is_received() {
+                MatchedAddress::SmBusDefault
+            } else if stat1.dumodf().is_saddr1() {
+                MatchedAddress::Secondary
+            } else {
+                MatchedAddress::Primary
+            };
+            Ok(SlaveEvent::AddressMatched {
+                read: stat1.tr().is_transmitter(),
+                address,
+            })
+        } else if stat0.rbne().bit_is_set() {
+            Ok(SlaveEvent::ByteReceived(self.i2c.data.read().trb().bits()))
+        } else if stat0.tbe().bit_is_set() {
+            Ok(SlaveEvent::ByteRequested)
+        } else if stat0.stpdet().bit_is_set() {
+            // Writing CTL0 after reading STAT0 clears STPDET
+            self.i2c.ctl0.modify(|_, w| w);
+            Ok(SlaveEvent::Stop)
+        } else {
+            Err(WouldBlock)
+        }
+    }
+
+    /// Sends a byte to the master in response to `ByteRequested`.
+    pub fn write(&mut self, byte: u8) {
+        self.i2c.data.write(|w| w.trb().bits(byte));
+    }
+
+    /// In SMBus mode with PEC, transfers the PEC after the current byte. When the master writes,
+    /// call this once the last data byte has been received, and the next byte will be checked as
+    /// the PEC. When the master reads, call this instead of `write` after the last data byte.
+    pub fn send_pec(&mut self) {
+        self.i2c.ctl0.modify(|_, w| w.pectrans().enabled());
+    }
+
+    /// In SMBus mode, drives the SMBALERT pin low to request the host's attention, or releases it.
+    /// The pin must be configured as an alternate function open-drain output.
+    pub fn set_alert(&mut self, alert: bool) {
+        self.i2c.ctl0.modify(|_, w| w.salt().bit(alert));
+    }
+
+    /// Releases the I2C peripheral and associated pins
+    pub fn free(self) -> (I2C, SCLPIN, SDAPIN) {
+        (self.i2c, self.scl_pin, self.sda_pin)
+    }
+}
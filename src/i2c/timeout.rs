@@ -0,0 +1,187 @@
+//! Time bases for the timeouts of the blocking I2C master
+
+use crate::pac::{TIMER0, TIMER1, TIMER13, TIMER2, TIMER5};
+use crate::rcu::Clocks;
+use crate::time::{Hertz, MicroSeconds};
+use crate::timer::{Timer, TimerExt};
+use cortex_m::peripheral::syst::SystClkSource;
+use cortex_m::peripheral::{DCB, DWT, SYST};
+
+/// Free-running counter that [`BlockingI2c`](super::BlockingI2c) measures its timeouts with
+pub trait TimeoutSource {
+    /// Returns the current value of the counter.
+    fn now(&self) -> u32;
+
+    /// Returns the number of ticks elapsed since `since`, which was returned by
+    /// [`TimeoutSource::now`].
+    fn elapsed(&self, since: u32) -> u32;
+
+    /// Converts a duration to ticks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the duration is longer than the counter can measure before wrapping around.
+    fn ticks(&self, duration: MicroSeconds) -> u32;
+}
+
+/// Converts a duration to ticks of a counter running at `frequency`, which wraps around after
+/// `max_ticks`.
+fn duration_to_ticks(duration: MicroSeconds, frequency: Hertz, max_ticks: u32) -> u32 {
+    let ticks = u64::from(duration.0) * u64::from(frequency.0) / 1_000_000;
+    assert!(ticks <= u64::from(max_ticks));
+    ticks as u32
+}
+
+/// Timeouts counted in CPU cycles by the DWT cycle counter
+///
+/// The cycle counter doesn't count while the core is halted, such as by a debugger or while
+/// semihosting.
+#[derive(Clone, Copy)]
+pub struct DwtTimeout {
+    frequency: Hertz,
+}
+
+impl DwtTimeout {
+    /// Enables the DWT cycle counter.
+    pub fn new(dcb: &mut DCB, dwt: &mut DWT, clocks: &Clocks) -> Self {
+        dcb.enable_trace();
+        dwt.enable_cycle_counter();
+
+        DwtTimeout {
+            frequency: clocks.hclk(),
+        }
+    }
+}
+
+impl TimeoutSource for DwtTimeout {
+    fn now(&self) -> u32 {
+        DWT::cycle_count()
+    }
+
+    fn elapsed(&self, since: u32) -> u32 {
+        DWT::cycle_count().wrapping_sub(since)
+    }
+
+    fn ticks(&self, duration: MicroSeconds) -> u32 {
+        duration_to_ticks(duration, self.frequency, u32::MAX)
+    }
+}
+
+/// Maximum value of the 24-bit SysTick counter
+const SYST_MAX: u32 = 0x00FF_FFFF;
+
+/// Timeouts counted by the SysTick timer, which runs freely at the core clock
+///
+/// Timeouts are limited to half the 24-bit range of the counter, as for [`TimerTimeout`], which is
+/// about 70 ms at 120 MHz.
+pub struct SysTickTimeout {
+    syst: SYST,
+    frequency: Hertz,
+}
+
+impl SysTickTimeout {
+    /// Starts the SysTick timer counting over its full range.
+    pub fn new(mut syst: SYST, clocks: &Clocks) -> Self {
+        syst.disable_interrupt();
+        syst.set_clock_source(SystClkSource::Core);
+        syst.set_reload(SYST_MAX);
+        syst.clear_current();
+        syst.enable_counter();
+
+        SysTickTimeout {
+            syst,
+            frequency: clocks.hclk(),
+        }
+    }
+
+    /// Stops the timer and releases the SYST
+    pub fn release(mut self) -> SYST {
+        self.syst.disable_counter();
+        self.syst
+    }
+}
+
+impl TimeoutSource for SysTickTimeout {
+    fn now(&self) -> u32 {
+        SYST::get_current()
+    }
+
+    fn elapsed(&self, since: u32) -> u32 {
+        // SysTick counts down
+        since.wrapping_sub(SYST::get_current()) & SYST_MAX
+    }
+
+    fn ticks(&self, duration: MicroSeconds) -> u32 {
+        duration_to_ticks(duration, self.frequency, SYST_MAX / 2)
+    }
+}
+
+/// Maximum number of ticks of a [`TimerTimeout`]
+///
+/// This is half the range of the 16-bit counter, so that a timeout is still noticed if the counter
+/// isn't checked again until some time after it expires.
+const TIMER_MAX_TICKS: u32 = u16::MAX as u32 / 2;
+
+/// Timeouts counted by a timer peripheral, which runs freely with a tick of about 1 µs
+///
+/// The timer clock is prescaled to the nearest whole number of MHz. Timeouts are limited to half
+/// the 16-bit range of the counter, about 32 ms.
+pub struct TimerTimeout<TIMER> {
+    timer: Timer<TIMER>,
+    frequency: Hertz,
+}
+
+macro_rules! timer_timeout {
+    ($($TIMERX:ident,)+) => {
+        $(
+            impl TimerTimeout<$TIMERX> {
+                /// Starts the timer counting microseconds over its full range.
+                pub fn new(mut timer: Timer<$TIMERX>) -> Self {
+                    let clock = timer.clock.0;
+                    let prescaler = ((clock + 500_000) / 1_000_000).max(1);
+
+                    let tim = &mut timer.timer;
+                    tim.ctl0.modify(|_, w| w.cen().disabled());
+                    tim.psc.write(|w| w.psc().bits((prescaler - 1) as u16));
+                    tim.car.write(|w| w.car().bits(u16::MAX.into()));
+                    // Trigger an update event to load the prescaler value
+                    tim.reset_counter();
+                    tim.ctl0.modify(|_, w| w.cen().enabled());
+
+                    TimerTimeout {
+                        timer,
+                        frequency: Hertz(clock / prescaler),
+                    }
+                }
+
+                /// Stops the timer and releases it
+                pub fn release(self) -> Timer<$TIMERX> {
+                    self.timer.timer.ctl0.modify(|_, w| w.cen().disabled());
+                    self.timer
+                }
+            }
+
+            impl TimeoutSource for TimerTimeout<$TIMERX> {
+                fn now(&self) -> u32 {
+                    u32::from(self.timer.timer.cnt.read().cnt().bits())
+                }
+
+                fn elapsed(&self, since: u32) -> u32 {
+                    self.now().wrapping_sub(since) & u32::from(u16::MAX)
+                }
+
+                fn ticks(&self, duration: MicroSeconds) -> u32 {
+                    duration_to_ticks(duration, self.frequency, TIMER_MAX_TICKS)
+                }
+            }
+        )+
+    };
+}
+
+timer_timeout! {
+    TIMER0,
+    TIMER1,
+    TIMER2,
+    TIMER5,
+    TIMER13,
+}
@@ -71,7 +71,7 @@ pub mod pwm;
 pub mod rcu;
 //pub mod rtc;
 pub mod serial;
-//pub mod spi;
+pub mod spi;
 pub mod time;
 pub mod timer;
 pub mod watchdog;
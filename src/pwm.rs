@@ -57,6 +57,32 @@ pub trait TimerRegExt {
     fn set_duty(&self, channel: Channel, duty: u16);
     fn get_max_duty(&self) -> u16;
     fn set_polarity(&self, channel: Channel, complementary: bool, polarity: Polarity);
+    /// Configures the channel for input capture from its own input pin.
+    fn configure_capture(&self, channel: Channel);
+    /// Returns whether a value has been captured on the channel since it was last read.
+    fn is_captured(&self, channel: Channel) -> bool;
+    fn get_counter(&self) -> u16;
+    /// Starts the counter running freely over its full range at the timer clock frequency.
+    fn start_free_running(&self);
+    /// Disables the channel and returns it to output mode, and stops the counter.
+    fn stop_capture(&self, channel: Channel);
+    /// Saves the registers which capturing on the channel changes.
+    fn save_capture_state(&self, channel: Channel) -> CaptureState;
+    /// Restores the registers saved by [`TimerRegExt::save_capture_state`], restarting the counter
+    /// if it was running.
+    fn restore_capture_state(&self, channel: Channel, state: CaptureState);
+}
+
+/// The timer configuration saved while a channel is borrowed for input capture.
+#[doc(hidden)]
+pub struct CaptureState {
+    ctl0: u32,
+    chctl: u32,
+    chctl2: u32,
+    cv: u32,
+    cnt: u32,
+    psc: u32,
+    car: u32,
 }
 
 #[doc(hidden)]
@@ -88,6 +114,27 @@ pub struct Ch1;
 pub struct Ch2;
 pub struct Ch3;
 
+#[doc(hidden)]
+pub trait ChannelId {
+    const CHANNEL: Channel;
+}
+
+impl ChannelId for Ch0 {
+    const CHANNEL: Channel = Channel::C0;
+}
+
+impl ChannelId for Ch1 {
+    const CHANNEL: Channel = Channel::C1;
+}
+
+impl ChannelId for Ch2 {
+    const CHANNEL: Channel = Channel::C2;
+}
+
+impl ChannelId for Ch3 {
+    const CHANNEL: Channel = Channel::C3;
+}
+
 pub trait Pin<TIMER, CHANNEL> {}
 pub trait ComplementaryPin<TIMER, CHANNEL> {}
 
@@ -553,7 +600,7 @@ macro_rules! hal {
 }
 
 macro_rules! timer_reg_ext {
-    ($timerX:ident, ($($channel:ident: $cv:ident, $val:ident, $p:ident $(/ $np:ident)?, $en:ident $(/ $nen:ident)? ;)+)) => {
+    ($timerX:ident, ($($channel:ident: $cv:ident, $val:ident, $p:ident $(/ $np:ident)?, $en:ident $(/ $nen:ident)?, $chctl_input:ident, $ms:ident, $if:ident ;)+)) => {
         impl TimerRegExt for $timerX::RegisterBlock {
             fn disable_channel(&self, channel: Channel, uses_complementary: bool) {
                 match channel {
@@ -640,6 +687,99 @@ macro_rules! timer_reg_ext {
             fn get_max_duty(&self) -> u16 {
                 self.car.read().car().bits() as u16
             }
+
+            fn configure_capture(&self, channel: Channel) {
+                match channel {
+                    $(
+                        Channel::$channel => {
+                            self.$chctl_input().modify(|_, w| w.$ms().ci0());
+                            // Clear any stale capture from before the channel was configured.
+                            self.intf.modify(|_, w| w.$if().clear());
+                        }
+                    )+
+                    #[allow(unreachable_patterns)]
+                    _ => panic!("No such channel {:?}", channel),
+                }
+            }
+
+            fn is_captured(&self, channel: Channel) -> bool {
+                match channel {
+                    $(
+                        Channel::$channel => self.intf.read().$if().is_capture_compare(),
+                    )+
+                    #[allow(unreachable_patterns)]
+                    _ => panic!("No such channel {:?}", channel),
+                }
+            }
+
+            fn get_counter(&self) -> u16 {
+                self.cnt.read().cnt().bits() as u16
+            }
+
+            fn start_free_running(&self) {
+                self.psc.write(|w| w.psc().bits(0));
+                self.car.write(|w| w.car().bits(0xFFFF));
+                self.swevg.write(|w| w.upg().update());
+                self.ctl0.modify(|_, w| w.cen().enabled());
+            }
+
+            fn stop_capture(&self, channel: Channel) {
+                self.disable_channel(channel, false);
+                match channel {
+                    $(
+                        Channel::$channel => self.$chctl_input().modify(|_, w| w.$ms().output()),
+                    )+
+                    #[allow(unreachable_patterns)]
+                    _ => panic!("No such channel {:?}", channel),
+                }
+                self.ctl0.modify(|_, w| w.cen().disabled());
+            }
+
+            fn save_capture_state(&self, channel: Channel) -> CaptureState {
+                let (chctl, cv) = match channel {
+                    $(
+                        Channel::$channel => (
+                            self.$chctl_input().read().bits(),
+                            self.$cv.read().bits(),
+                        ),
+                    )+
+                    #[allow(unreachable_patterns)]
+                    _ => panic!("No such channel {:?}", channel),
+                };
+                CaptureState {
+                    ctl0: self.ctl0.read().bits(),
+                    chctl,
+                    chctl2: self.chctl2.read().bits(),
+                    cv,
+                    cnt: self.cnt.read().bits(),
+                    psc: self.psc.read().bits(),
+                    car: self.car.read().bits(),
+                }
+            }
+
+            fn restore_capture_state(&self, channel: Channel, state: CaptureState) {
+                self.ctl0.modify(|_, w| w.cen().disabled());
+                match channel {
+                    $(
+                        Channel::$channel => {
+                            self.$chctl_input().write(|w| unsafe { w.bits(state.chctl) });
+                            self.$cv.write(|w| unsafe { w.bits(state.cv) });
+                        }
+                    )+
+                    #[allow(unreachable_patterns)]
+                    _ => panic!("No such channel {:?}", channel),
+                }
+                self.chctl2.write(|w| unsafe { w.bits(state.chctl2) });
+                self.psc.write(|w| unsafe { w.bits(state.psc) });
+                self.car.write(|w| unsafe { w.bits(state.car) });
+
+                // Load the prescaler and shadow registers without setting the update flag, which
+                // also resets the counter, so restore that afterwards.
+                self.ctl0.modify(|_, w| w.ups().set_bit());
+                self.swevg.write(|w| w.upg().update());
+                self.cnt.write(|w| unsafe { w.bits(state.cnt) });
+                self.ctl0.write(|w| unsafe { w.bits(state.ctl0) });
+            }
         }
     };
 }
@@ -696,10 +836,10 @@ impl Pin<TIMER1, Ch3> for PB11<Alternate> {}
 
 // Some timers share the same PAC types so we don't need this for all of them.
 timer_reg_ext!(timer0, (
-    C0: ch0cv, ch0val, ch0p/ch0np, ch0en/ch0nen;
-    C1: ch1cv, ch1val, ch1p/ch1np, ch1en/ch1nen;
-    C2: ch2cv, ch2val, ch2p/ch2np, ch2en/ch2nen;
-    C3: ch3cv, ch3val, ch3p, ch3en;
+    C0: ch0cv, ch0val, ch0p/ch0np, ch0en/ch0nen, chctl0_input, ch0ms, ch0if;
+    C1: ch1cv, ch1val, ch1p/ch1np, ch1en/ch1nen, chctl0_input, ch1ms, ch1if;
+    C2: ch2cv, ch2val, ch2p/ch2np, ch2en/ch2nen, chctl1_input, ch2ms, ch2if;
+    C3: ch3cv, ch3val, ch3p, ch3en, chctl1_input, ch3ms, ch3if;
 ));
 timer_idle_reg_ext!(timer0, (
     C0: iso0/iso0n;
@@ -708,10 +848,10 @@ timer_idle_reg_ext!(timer0, (
     C3: iso3;
 ));
 timer_reg_ext!(timer1, (
-    C0: ch0cv, ch0val, ch0p, ch0en;
-    C1: ch1cv, ch1val, ch1p, ch1en;
-    C2: ch2cv, ch2val, ch2p, ch2en;
-    C3: ch3cv, ch3val, ch3p, ch3en;
+    C0: ch0cv, ch0val, ch0p, ch0en, chctl0_input, ch0ms, ch0if;
+    C1: ch1cv, ch1val, ch1p, ch1en, chctl0_input, ch1ms, ch1if;
+    C2: ch2cv, ch2val, ch2p, ch2en, chctl1_input, ch2ms, ch2if;
+    C3: ch3cv, ch3val, ch3p, ch3en, chctl1_input, ch3ms, ch3if;
 ));
 
 hal!(TIMER0: (timer0, cchp));
@@ -680,10 +680,12 @@ bus! {
     GPIOC => (APB2, pcen, pcrst),
     GPIOD => (APB2, pden, pdrst),
     GPIOE => (APB2, peen, perst),
+    AFIO => (APB2, afen, afrst),
 }
 
 ahb_bus! {
     CRC => (crcen),
     DMA0 => (dma0en),
+    DMA1 => (dma1en),
     USBFS_GLOBAL => (usbfsen),
 }
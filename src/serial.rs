@@ -8,22 +8,35 @@
 //! synchronous asynchronous receiver transmitter).
 
 use crate::dma::{
-    self, CircBuffer, CircReadDma, Priority, ReadDma, Receive, RxDma, Transfer, TransferPayload,
-    Transmit, TxDma, Width, WriteDma, R, W,
+    dma0, dma1, CircBuffer, CircReadDma, Priority, ReadDma, Receive, RxDma, Transfer,
+    TransferPayload, Transmit, TxDma, Width, WriteDma, R, W,
 };
-use crate::pac::{self, usart0, usart0::ctl1::STB_A, USART0};
+use crate::gpio::{Alternate, OpenDrain, Pin};
+use crate::pac::{
+    self,
+    usart0::{self, ctl0::WM_A, ctl1::LBLEN_A, ctl1::STB_A},
+    UART3, UART4, USART0, USART1, USART2,
+};
+use crate::pwm::{self, ChannelId, TimerRegExt};
 use crate::rcu::{sealed::RcuBus, Clocks, Enable, GetBusFreq, Reset};
-use crate::time::{Bps, U32Ext};
+use crate::time::{Bps, Hertz, MicroSeconds, U32Ext};
+use crate::timer::Timer;
 use core::convert::Infallible;
 use core::fmt;
 use core::marker::PhantomData;
 use core::ops::Deref;
 use core::sync::atomic::{self, Ordering};
 use embedded_dma::{ReadBuffer, WriteBuffer};
+use embedded_hal::digital::v2::OutputPin;
 use embedded_hal::serial::{Read, Write};
+use embedded_hal::spi::{self, Mode, Phase, Polarity};
+
+mod buffered;
+
+pub use buffered::{BufferedSerial, BufferedSerialInterrupt, SerialBuffers, XonXoff, XOFF, XON};
 
 /// Serial error
-#[derive(Debug)]
+#[derive(Debug, Eq, PartialEq)]
 #[non_exhaustive]
 pub enum Error {
     /// Framing error
@@ -34,6 +47,10 @@ pub enum Error {
     Overrun,
     /// Parity check error
     Parity,
+    /// LIN break detected
+    Break,
+    /// Malformed answer to reset received from a smartcard
+    InvalidAtr,
 }
 
 pub enum Parity {
@@ -44,10 +61,53 @@ pub enum Parity {
 
 pub type StopBits = STB_A;
 
+/// How the receiver wakes up from mute mode.
+pub type WakeupMethod = WM_A;
+
+/// The length of break to detect in LIN mode.
+pub type LinBreakLength = LBLEN_A;
+
+/// The number of data bits in each frame, not counting the parity bit if any.
+pub enum WordLength {
+    /// 8 data bits.
+    DataBits8,
+    /// 9 data bits. This can't be used together with parity, as the hardware only supports frames
+    /// of up to 9 bits including the parity bit.
+    DataBits9,
+}
+
+/// Hardware flow control. This is only available on USART0, USART1 and USART2, and needs the RTS
+/// pin to be passed along with the RX pin and the CTS pin along with the TX pin.
+pub enum FlowControl {
+    /// No hardware flow control.
+    None,
+    /// Deassert RTS when the receive buffer is full.
+    Rts,
+    /// Only transmit while CTS is asserted.
+    Cts,
+    /// Both RTS and CTS flow control.
+    RtsCts,
+}
+
+/// IrDA SIR encoder/decoder mode.
+pub enum IrdaMode {
+    /// IrDA is disabled, the USART uses normal NRZ encoding.
+    Disabled,
+    /// IrDA SIR with pulses of 3/16 of a bit period.
+    Normal,
+    /// IrDA SIR low-power mode, with pulses of 3 periods of a clock prescaled to around 1.8 MHz.
+    LowPower,
+}
+
 pub struct Config {
     pub baudrate: Bps,
+    pub wordlength: WordLength,
     pub parity: Parity,
     pub stopbits: StopBits,
+    pub flow_control: FlowControl,
+    pub irda: IrdaMode,
+    /// The maximum error in percent between the requested and achieved baud rates.
+    pub max_baudrate_error: f32,
 }
 
 impl Config {
@@ -56,6 +116,11 @@ impl Config {
         self
     }
 
+    pub fn wordlength(mut self, wordlength: WordLength) -> Self {
+        self.wordlength = wordlength;
+        self
+    }
+
     pub fn parity_none(mut self) -> Self {
         self.parity = Parity::ParityNone;
         self
@@ -75,18 +140,69 @@ impl Config {
         self.stopbits = stopbits;
         self
     }
+
+    pub fn flow_control(mut self, flow_control: FlowControl) -> Self {
+        self.flow_control = flow_control;
+        self
+    }
+
+    pub fn irda(mut self, irda: IrdaMode) -> Self {
+        self.irda = irda;
+        self
+    }
+
+    pub fn max_baudrate_error(mut self, max_baudrate_error: f32) -> Self {
+        self.max_baudrate_error = max_baudrate_error;
+        self
+    }
 }
 
 impl Default for Config {
     fn default() -> Config {
         Config {
             baudrate: 115_200_u32.bps(),
+            wordlength: WordLength::DataBits8,
             parity: Parity::ParityNone,
             stopbits: StopBits::STOP1,
+            flow_control: FlowControl::None,
+            irda: IrdaMode::Disabled,
+            max_baudrate_error: 2.0,
         }
     }
 }
 
+/// The baud rate achieved by a configuration.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BaudRate {
+    pub achieved: Bps,
+    /// The error relative to the requested baud rate, in percent.
+    pub error_percent: f32,
+}
+
+/// Serial configuration error
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum ConfigError {
+    /// The baud rate is too high or too low for the bus clock.
+    BaudRateOutOfRange,
+    /// The achieved baud rate differs from the requested one by more than the configured maximum.
+    BaudRateInaccurate(BaudRate),
+    /// Parity was requested with 9 data bits, which the hardware doesn't support.
+    ParityWithNineDataBits,
+    /// The bus clock can't be prescaled to the frequency needed for IrDA low-power mode.
+    IrdaPrescalerOutOfRange,
+    /// The smartcard clock is too high or too low for the bus clock.
+    SmartcardClockOutOfRange,
+    /// More than 7 smartcard retransmissions were requested.
+    SmartcardRetriesOutOfRange,
+    /// Hardware flow control was requested without passing the RTS or CTS pin it needs.
+    FlowControlWithoutPin,
+    /// No sync byte was received while detecting the baud rate.
+    AutobaudTimeout,
+    /// Something other than a sync byte was received while detecting the baud rate.
+    AutobaudInvalidSync,
+}
+
 /// Interrupt event
 pub enum Event {
     /// New data has been received
@@ -95,40 +211,142 @@ pub enum Event {
     Tbe,
     /// Idle line state detected
     Idle,
+    /// CTS line changed state
+    Cts,
+    /// LIN break detected
+    LinBreak,
+}
+
+/// The register block shared by all USART and UART peripherals.
+///
+/// UART3 and UART4 have a subset of the USART registers, at the same offsets, so they are accessed
+/// through the USART0 register block too. `CTL3`, `RT` and `STAT1` must not be used with them.
+pub type UsartRegisterBlock = usart0::RegisterBlock;
+
+/// A USART or UART peripheral which can be used with [`Serial`].
+pub trait Instance: RcuBus + Enable + Reset {
+    /// Returns the registers of the peripheral.
+    #[doc(hidden)]
+    fn registers(&self) -> &UsartRegisterBlock;
+}
+
+macro_rules! instance {
+    ($($USARTX:ty,)+) => {
+        $(
+            impl Instance for $USARTX {
+                fn registers(&self) -> &UsartRegisterBlock {
+                    // NOTE(unsafe) The register block is compatible, see `UsartRegisterBlock`.
+                    unsafe { &*<$USARTX>::ptr().cast() }
+                }
+            }
+        )+
+    };
 }
 
-pub trait TxPin<USART> {}
-pub trait RxPin<USART> {}
+instance!(USART0, USART1, USART2, UART3, UART4,);
+
+pub trait TxPin<USART> {
+    /// Whether a CTS pin is passed along with the TX pin.
+    #[doc(hidden)]
+    const CTS: bool = false;
+}
+pub trait RxPin<USART> {
+    /// Whether an RTS pin is passed along with the RX pin.
+    #[doc(hidden)]
+    const RTS: bool = false;
+}
+pub trait RtsPin<USART> {}
+pub trait CtsPin<USART> {}
+pub trait CkPin<USART> {}
+
+// For hardware flow control, the CTS pin is passed along with the TX pin and the RTS pin along with
+// the RX pin. Flow control can only be enabled in a direction for which the pin is passed. UART3 and
+// UART4 have no RTS or CTS pins, so don't support flow control.
+impl<USART, TX: TxPin<USART>, CTS: CtsPin<USART>> TxPin<USART> for (TX, CTS) {
+    const CTS: bool = true;
+}
+impl<USART, RX: RxPin<USART>, RTS: RtsPin<USART>> RxPin<USART> for (RX, RTS) {
+    const RTS: bool = true;
+}
 
-// Two USARTs
+// Three USARTs and two UARTs. Remapping is configured with AFIO_PCF0.
 mod pins {
     use super::*;
-    use crate::gpio::gpioa::{PA10, PA2, PA3, PA9};
-    use crate::gpio::gpiob::{PB6, PB7};
-    use crate::gpio::gpiod::{PD5, PD7};
-    use crate::gpio::Alternate;
-    use crate::pac::USART1;
+    use crate::gpio::gpioa::{PA0, PA1, PA10, PA11, PA12, PA2, PA3, PA4, PA8, PA9};
+    use crate::gpio::gpiob::{PB10, PB11, PB12, PB13, PB14, PB6, PB7};
+    use crate::gpio::gpioc::{PC10, PC11, PC12};
+    use crate::gpio::gpiod::{PD10, PD11, PD12, PD2, PD3, PD4, PD5, PD6, PD7, PD8, PD9};
 
-    impl TxPin<USART0> for PA9<Alternate> {}
+    impl<MODE> TxPin<USART0> for PA9<Alternate<MODE>> {}
     impl RxPin<USART0> for PA10<Alternate> {}
-    impl TxPin<USART0> for PB6<Alternate> {}
+    impl CtsPin<USART0> for PA11<Alternate> {}
+    impl RtsPin<USART0> for PA12<Alternate> {}
+    impl CkPin<USART0> for PA8<Alternate> {}
+    // Remap
+    impl<MODE> TxPin<USART0> for PB6<Alternate<MODE>> {}
     impl RxPin<USART0> for PB7<Alternate> {}
 
-    impl TxPin<USART1> for PA2<Alternate> {}
+    impl<MODE> TxPin<USART1> for PA2<Alternate<MODE>> {}
     impl RxPin<USART1> for PA3<Alternate> {}
-    impl TxPin<USART1> for PD5<Alternate> {}
-    impl RxPin<USART1> for PD7<Alternate> {}
+    impl CtsPin<USART1> for PA0<Alternate> {}
+    impl RtsPin<USART1> for PA1<Alternate> {}
+    impl CkPin<USART1> for PA4<Alternate> {}
+    // Remap
+    impl<MODE> TxPin<USART1> for PD5<Alternate<MODE>> {}
+    impl RxPin<USART1> for PD6<Alternate> {}
+    impl CtsPin<USART1> for PD3<Alternate> {}
+    impl RtsPin<USART1> for PD4<Alternate> {}
+    impl CkPin<USART1> for PD7<Alternate> {}
+
+    impl<MODE> TxPin<USART2> for PB10<Alternate<MODE>> {}
+    impl RxPin<USART2> for PB11<Alternate> {}
+    // No remap and partial remap
+    impl CtsPin<USART2> for PB13<Alternate> {}
+    impl RtsPin<USART2> for PB14<Alternate> {}
+    impl CkPin<USART2> for PB12<Alternate> {}
+    // Partial remap
+    impl<MODE> TxPin<USART2> for PC10<Alternate<MODE>> {}
+    impl RxPin<USART2> for PC11<Alternate> {}
+    impl CkPin<USART2> for PC12<Alternate> {}
+    // Full remap
+    impl<MODE> TxPin<USART2> for PD8<Alternate<MODE>> {}
+    impl RxPin<USART2> for PD9<Alternate> {}
+    impl CtsPin<USART2> for PD11<Alternate> {}
+    impl RtsPin<USART2> for PD12<Alternate> {}
+    impl CkPin<USART2> for PD10<Alternate> {}
+
+    impl<MODE> TxPin<UART3> for PC10<Alternate<MODE>> {}
+    impl RxPin<UART3> for PC11<Alternate> {}
+
+    impl<MODE> TxPin<UART4> for PC12<Alternate<MODE>> {}
+    impl RxPin<UART4> for PD2<Alternate> {}
 }
 
 /// Serial abstraction
 pub struct Serial<USART, TXPIN, RXPIN> {
     usart: USART,
     pins: (TXPIN, RXPIN),
+    flow_control_pins: FlowControlPins,
+}
+
+/// Which of the RTS and CTS pins were passed, and so which flow control can be enabled.
+#[derive(Clone, Copy)]
+struct FlowControlPins {
+    rts: bool,
+    cts: bool,
+}
+
+impl FlowControlPins {
+    const NONE: Self = Self {
+        rts: false,
+        cts: false,
+    };
 }
 
 /// Serial receiver
 pub struct Rx<USART> {
-    usart: *const usart0::RegisterBlock,
+    usart: *const UsartRegisterBlock,
+    flow_control_pins: FlowControlPins,
     _instance: PhantomData<USART>,
 }
 
@@ -136,21 +354,22 @@ unsafe impl<USART> Send for Rx<USART> {}
 
 /// Serial transmitter
 pub struct Tx<USART> {
-    usart: *const usart0::RegisterBlock,
+    usart: *const UsartRegisterBlock,
+    flow_control_pins: FlowControlPins,
     _instance: PhantomData<USART>,
 }
 
 unsafe impl<USART> Send for Tx<USART> {}
 
-impl<
-        USART: RcuBus + Enable + Reset + Deref<Target = usart0::RegisterBlock>,
-        TXPIN: TxPin<USART>,
-        RXPIN: RxPin<USART>,
-    > Serial<USART, TXPIN, RXPIN>
+impl<USART: Instance, TXPIN: TxPin<USART>, RXPIN: RxPin<USART>> Serial<USART, TXPIN, RXPIN>
 where
     USART::Bus: GetBusFreq,
 {
     /// Configures the USART and creates a new Serial instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is rejected, see [`Serial::try_usart`].
     pub fn usart(
         usart: USART,
         pins: (TXPIN, RXPIN),
@@ -158,39 +377,66 @@ where
         clocks: Clocks,
         bus: &mut USART::Bus,
     ) -> Self {
-        usart.enable_configure(config, clocks, bus);
+        Self::try_usart(usart, pins, config, clocks, bus).unwrap().0
+    }
+
+    /// Configures the USART and creates a new Serial instance, returning it along with the
+    /// achieved baud rate.
+    pub fn try_usart(
+        usart: USART,
+        pins: (TXPIN, RXPIN),
+        config: Config,
+        clocks: Clocks,
+        bus: &mut USART::Bus,
+    ) -> Result<(Self, BaudRate), ConfigError> {
+        let flow_control_pins = FlowControlPins {
+            rts: RXPIN::RTS,
+            cts: TXPIN::CTS,
+        };
+        let baud_rate = usart.enable_configure(config, flow_control_pins, clocks, bus)?;
 
         // Enable transmitter, receiver and the USART as a whole.
         usart
+            .registers()
             .ctl0
             .modify(|_, w| w.ten().enabled().ren().enabled().uen().enabled());
 
-        Self { usart, pins }
+        Ok((
+            Self {
+                usart,
+                pins,
+                flow_control_pins,
+            },
+            baud_rate,
+        ))
     }
 
     /// Separates the serial struct into separate channel objects for sending (Tx) and receiving (Rx).
     pub fn split(self) -> (Tx<USART>, Rx<USART>) {
         (
             Tx {
-                usart: &*self.usart,
+                usart: self.usart.registers(),
+                flow_control_pins: self.flow_control_pins,
                 _instance: PhantomData,
             },
             Rx {
-                usart: &*self.usart,
+                usart: self.usart.registers(),
+                flow_control_pins: self.flow_control_pins,
                 _instance: PhantomData,
             },
         )
     }
 }
 
-impl<
-        USART: RcuBus + Enable + Reset + Deref<Target = usart0::RegisterBlock>,
-        TXPIN: TxPin<USART>,
-    > Serial<USART, TXPIN, ()>
+impl<USART: Instance, TXPIN: TxPin<USART>> Serial<USART, TXPIN, ()>
 where
     USART::Bus: GetBusFreq,
 {
     /// Configures the USART and creates a new TX-only Serial instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is rejected, see [`Serial::try_usart_tx`].
     pub fn usart_tx(
         usart: USART,
         txpin: TXPIN,
@@ -198,34 +444,61 @@ where
         clocks: Clocks,
         bus: &mut USART::Bus,
     ) -> Self {
-        usart.enable_configure(config, clocks, bus);
+        Self::try_usart_tx(usart, txpin, config, clocks, bus)
+            .unwrap()
+            .0
+    }
+
+    /// Configures the USART and creates a new TX-only Serial instance, returning it along with the
+    /// achieved baud rate.
+    pub fn try_usart_tx(
+        usart: USART,
+        txpin: TXPIN,
+        config: Config,
+        clocks: Clocks,
+        bus: &mut USART::Bus,
+    ) -> Result<(Self, BaudRate), ConfigError> {
+        let flow_control_pins = FlowControlPins {
+            rts: false,
+            cts: TXPIN::CTS,
+        };
+        let baud_rate = usart.enable_configure(config, flow_control_pins, clocks, bus)?;
 
         // Enable transmitter and the USART as a whole.
-        usart.ctl0.modify(|_, w| w.ten().enabled().uen().enabled());
+        usart
+            .registers()
+            .ctl0
+            .modify(|_, w| w.ten().enabled().uen().enabled());
 
-        Self {
-            usart,
-            pins: (txpin, ()),
-        }
+        Ok((
+            Self {
+                usart,
+                pins: (txpin, ()),
+                flow_control_pins,
+            },
+            baud_rate,
+        ))
     }
 
     /// Erase the pin.
     pub fn downgrade_tx(self) -> Tx<USART> {
         Tx {
-            usart: &*self.usart,
+            usart: self.usart.registers(),
+            flow_control_pins: self.flow_control_pins,
             _instance: PhantomData,
         }
     }
 }
 
-impl<
-        USART: RcuBus + Enable + Reset + Deref<Target = usart0::RegisterBlock>,
-        RXPIN: RxPin<USART>,
-    > Serial<USART, (), RXPIN>
+impl<USART: Instance, RXPIN: RxPin<USART>> Serial<USART, (), RXPIN>
 where
     USART::Bus: GetBusFreq,
 {
     /// Configures the USART and creates a new RX-only Serial instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is rejected, see [`Serial::try_usart_rx`].
     pub fn usart_rx(
         usart: USART,
         rxpin: RXPIN,
@@ -233,51 +506,232 @@ where
         clocks: Clocks,
         bus: &mut USART::Bus,
     ) -> Self {
-        usart.enable_configure(config, clocks, bus);
+        Self::try_usart_rx(usart, rxpin, config, clocks, bus)
+            .unwrap()
+            .0
+    }
+
+    /// Configures the USART and creates a new RX-only Serial instance, returning it along with the
+    /// achieved baud rate.
+    pub fn try_usart_rx(
+        usart: USART,
+        rxpin: RXPIN,
+        config: Config,
+        clocks: Clocks,
+        bus: &mut USART::Bus,
+    ) -> Result<(Self, BaudRate), ConfigError> {
+        let flow_control_pins = FlowControlPins {
+            rts: RXPIN::RTS,
+            cts: false,
+        };
+        let baud_rate = usart.enable_configure(config, flow_control_pins, clocks, bus)?;
 
         // Enable receiver and the USART as a whole.
-        usart.ctl0.modify(|_, w| w.ren().enabled().uen().enabled());
+        usart
+            .registers()
+            .ctl0
+            .modify(|_, w| w.ren().enabled().uen().enabled());
 
-        Self {
-            usart,
-            pins: ((), rxpin),
-        }
+        Ok((
+            Self {
+                usart,
+                pins: ((), rxpin),
+                flow_control_pins,
+            },
+            baud_rate,
+        ))
     }
 
     /// Erase the pin.
     pub fn downgrade_rx(self) -> Rx<USART> {
         Rx {
-            usart: &*self.usart,
+            usart: self.usart.registers(),
+            flow_control_pins: self.flow_control_pins,
             _instance: PhantomData,
         }
     }
 }
 
-impl<USART: Deref<Target = usart0::RegisterBlock>, TXPIN, RXPIN> Serial<USART, TXPIN, RXPIN> {
+impl<USART: Instance, TXPIN, RXPIN> Serial<USART, TXPIN, RXPIN> {
     pub fn release(self) -> (USART, (TXPIN, RXPIN)) {
         (self.usart, self.pins)
     }
 
+    /// Changes the configuration of the USART, once any ongoing transmission is complete. If the
+    /// configuration is rejected, the USART is left unchanged.
+    pub fn reconfigure(&mut self, config: Config, clocks: &Clocks) -> Result<BaudRate, ConfigError>
+    where
+        USART::Bus: GetBusFreq,
+    {
+        reconfigure::<USART>(
+            self.usart.registers(),
+            config,
+            self.flow_control_pins,
+            clocks,
+        )
+    }
+
+    /// Detects the baud rate of a host from a sync byte, and reconfigures the USART to use it.
+    ///
+    /// This blocks until a 0x55 or 0x7F byte is received, then measures the length of its start bit
+    /// by capturing the edges of the RX pin on the given timer channel. The widths of the following
+    /// pulses are checked to match the sync byte. The sync byte itself is discarded. The slowest baud
+    /// rate which can be detected is the timer clock frequency divided by 8738, so that the seven
+    /// bit high pulse of 0x7F can be measured with the 16-bit counter.
+    /// The timer's configuration and counter are restored once the measurement is done.
+    ///
+    /// Returns [`ConfigError::AutobaudTimeout`] if no sync byte starts within `timeout`, and
+    /// [`ConfigError::AutobaudInvalidSync`] if something other than a sync byte is received.
+    pub fn detect_baudrate<TIMER, RB, CHANNEL>(
+        &mut self,
+        timer: &mut Timer<TIMER>,
+        config: Config,
+        clocks: &Clocks,
+        timeout: MicroSeconds,
+    ) -> Result<BaudRate, ConfigError>
+    where
+        USART::Bus: GetBusFreq,
+        TIMER: Deref<Target = RB>,
+        RB: TimerRegExt,
+        RXPIN: pwm::Pin<TIMER, CHANNEL>,
+        CHANNEL: ChannelId,
+    {
+        let channel = CHANNEL::CHANNEL;
+        let capture = &*timer.timer;
+        let timeout_ticks = u64::from(timer.clock.0) * u64::from(timeout.0) / 1_000_000;
+
+        // The timer is borrowed, so put its configuration back once the measurement is done.
+        let saved = capture.save_capture_state(channel);
+        capture.configure_capture(channel);
+        capture.set_polarity(channel, false, pwm::Polarity::Inverted);
+        capture.start_free_running();
+        capture.enable_channel(channel, false);
+        let bit_ticks = measure_sync_byte(capture, channel, timeout_ticks);
+        capture.stop_capture(channel);
+        capture.restore_capture_state(channel, saved);
+        let bit_ticks = bit_ticks?;
+
+        let baudrate = timer.clock.0 / bit_ticks;
+        let usart = self.usart.registers();
+        let result = reconfigure::<USART>(
+            usart,
+            config.baudrate(baudrate.bps()),
+            self.flow_control_pins,
+            clocks,
+        );
+
+        // Discard whatever was received at the old baud rate, and clear any errors.
+        usart.stat0.read();
+        usart.data.read();
+        result
+    }
+
     /// Enable an interrupt event.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`Event::Cts`] is requested without the CTS pin having been passed.
     pub fn listen(&mut self, event: Event) {
+        let usart = self.usart.registers();
         match event {
-            Event::Rbne => self.usart.ctl0.modify(|_, w| w.rbneie().enabled()),
-            Event::Tbe => self.usart.ctl0.modify(|_, w| w.tbeie().enabled()),
-            Event::Idle => self.usart.ctl0.modify(|_, w| w.idleie().enabled()),
+            Event::Rbne => usart.ctl0.modify(|_, w| w.rbneie().enabled()),
+            Event::Tbe => usart.ctl0.modify(|_, w| w.tbeie().enabled()),
+            Event::Idle => usart.ctl0.modify(|_, w| w.idleie().enabled()),
+            Event::Cts => {
+                assert!(self.flow_control_pins.cts);
+                usart.ctl2.modify(|_, w| w.ctsie().enabled())
+            }
+            Event::LinBreak => usart.ctl1.modify(|_, w| w.lbdie().enabled()),
         }
     }
 
     /// Disable an interrupt event.
     pub fn unlisten(&mut self, event: Event) {
+        let usart = self.usart.registers();
         match event {
-            Event::Rbne => self.usart.ctl0.modify(|_, w| w.rbneie().disabled()),
-            Event::Tbe => self.usart.ctl0.modify(|_, w| w.tbeie().disabled()),
-            Event::Idle => self.usart.ctl0.modify(|_, w| w.idleie().disabled()),
+            Event::Rbne => usart.ctl0.modify(|_, w| w.rbneie().disabled()),
+            Event::Tbe => usart.ctl0.modify(|_, w| w.tbeie().disabled()),
+            Event::Idle => usart.ctl0.modify(|_, w| w.idleie().disabled()),
+            Event::Cts => usart.ctl2.modify(|_, w| w.ctsie().disabled()),
+            Event::LinBreak => usart.ctl1.modify(|_, w| w.lbdie().disabled()),
+        }
+    }
+
+    /// Transmits a break frame once the current transmission is complete.
+    pub fn send_break(&mut self) {
+        self.usart
+            .registers()
+            .ctl0
+            .modify(|_, w| w.sbkcmd().enabled());
+    }
+
+    /// Returns whether the CTS line has changed state since this was last called, and clears the
+    /// flag.
+    pub fn cts_changed(&mut self) -> bool {
+        let usart = self.usart.registers();
+        let changed = usart.stat0.read().ctsf().bit_is_set();
+        if changed {
+            // The flags are cleared by writing 0, so write 1 to the others to leave them as they are
+            // even if they were set since the read.
+            usart.stat0.write(|w| {
+                w.rbne()
+                    .set_bit()
+                    .tc()
+                    .set_bit()
+                    .lbdf()
+                    .set_bit()
+                    .ctsf()
+                    .clear_bit()
+            });
         }
+        changed
+    }
+
+    /// Configures how the receiver wakes up from mute mode, and the 4-bit address of this node.
+    ///
+    /// With address mark wakeup, the most significant bit of a word marks it as an address, and the
+    /// receiver wakes up when the lower 4 bits of an address match the node address. The address is
+    /// ignored for idle line wakeup.
+    pub fn set_wakeup(&mut self, method: WakeupMethod, address: u8) {
+        assert!(address <= 0x0F);
+        let usart = self.usart.registers();
+        usart.ctl0.modify(|_, w| w.wm().variant(method));
+        usart.ctl1.modify(|_, w| unsafe { w.addr().bits(address) });
+    }
+
+    /// Puts the receiver in mute mode, so that received words are ignored until it wakes up.
+    pub fn mute(&mut self) {
+        self.usart.registers().ctl0.modify(|_, w| w.rwu().enabled());
+    }
+
+    /// Returns whether the receiver is in mute mode.
+    pub fn is_muted(&self) -> bool {
+        self.usart.registers().ctl0.read().rwu().is_enabled()
+    }
+
+    /// Sends an address word, with the 9th bit set to mark it as an address. The USART must be
+    /// configured with 9 data bits.
+    pub fn send_address(&mut self, address: u8) -> nb::Result<(), Infallible> {
+        self.usart.registers().write(0x100 | u16::from(address))
     }
 }
 
-impl<USART: Deref<Target = usart0::RegisterBlock>> Rx<USART> {
+impl<USART: Instance> Rx<USART> {
+    /// Changes the configuration of the USART, once any ongoing transmission is complete. This
+    /// also affects the corresponding [`Tx`]. If the configuration is rejected, the USART is left
+    /// unchanged.
+    pub fn reconfigure(&mut self, config: Config, clocks: &Clocks) -> Result<BaudRate, ConfigError>
+    where
+        USART::Bus: GetBusFreq,
+    {
+        reconfigure::<USART>(
+            unsafe { &*self.usart },
+            config,
+            self.flow_control_pins,
+            clocks,
+        )
+    }
+
     /// Enable the RBNE interrupt.
     pub fn listen(&mut self) {
         unsafe { &*self.usart }
@@ -293,7 +747,22 @@ impl<USART: Deref<Target = usart0::RegisterBlock>> Rx<USART> {
     }
 }
 
-impl<USART: Deref<Target = usart0::RegisterBlock>> Tx<USART> {
+impl<USART: Instance> Tx<USART> {
+    /// Changes the configuration of the USART, once any ongoing transmission is complete. This
+    /// also affects the corresponding [`Rx`]. If the configuration is rejected, the USART is left
+    /// unchanged.
+    pub fn reconfigure(&mut self, config: Config, clocks: &Clocks) -> Result<BaudRate, ConfigError>
+    where
+        USART::Bus: GetBusFreq,
+    {
+        reconfigure::<USART>(
+            unsafe { &*self.usart },
+            config,
+            self.flow_control_pins,
+            clocks,
+        )
+    }
+
     /// Enable the TBE interrupt.
     pub fn listen(&mut self) {
         unsafe { &*self.usart }
@@ -310,21 +779,31 @@ impl<USART: Deref<Target = usart0::RegisterBlock>> Tx<USART> {
 }
 
 // Implement writing traits if the USART has a TX pin assigned.
-impl<USART: Deref<Target = usart0::RegisterBlock>, TXPIN: TxPin<USART>, RXPIN> Write<u8>
-    for Serial<USART, TXPIN, RXPIN>
-{
+impl<USART: Instance, TXPIN: TxPin<USART>, RXPIN> Write<u8> for Serial<USART, TXPIN, RXPIN> {
     type Error = Infallible;
 
     fn flush(&mut self) -> nb::Result<(), Self::Error> {
-        self.usart.flush()
+        self.usart.registers().flush()
     }
 
     fn write(&mut self, byte: u8) -> nb::Result<(), Self::Error> {
-        self.usart.write(byte)
+        self.usart.registers().write(byte.into())
+    }
+}
+
+impl<USART: Instance, TXPIN: TxPin<USART>, RXPIN> Write<u16> for Serial<USART, TXPIN, RXPIN> {
+    type Error = Infallible;
+
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        self.usart.registers().flush()
+    }
+
+    fn write(&mut self, word: u16) -> nb::Result<(), Self::Error> {
+        self.usart.registers().write(word)
     }
 }
 
-impl<USART: Deref<Target = usart0::RegisterBlock>> Write<u8> for Tx<USART> {
+impl<USART: Instance> Write<u8> for Tx<USART> {
     type Error = Infallible;
 
     fn flush(&mut self) -> nb::Result<(), Self::Error> {
@@ -332,13 +811,23 @@ impl<USART: Deref<Target = usart0::RegisterBlock>> Write<u8> for Tx<USART> {
     }
 
     fn write(&mut self, byte: u8) -> nb::Result<(), Self::Error> {
-        unsafe { &*self.usart }.write(byte)
+        unsafe { &*self.usart }.write(byte.into())
     }
 }
 
-impl<USART: Deref<Target = usart0::RegisterBlock>, TXPIN: TxPin<USART>, RXPIN> fmt::Write
-    for Serial<USART, TXPIN, RXPIN>
-{
+impl<USART: Instance> Write<u16> for Tx<USART> {
+    type Error = Infallible;
+
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        unsafe { &*self.usart }.flush()
+    }
+
+    fn write(&mut self, word: u16) -> nb::Result<(), Self::Error> {
+        unsafe { &*self.usart }.write(word)
+    }
+}
+
+impl<USART: Instance, TXPIN: TxPin<USART>, RXPIN> fmt::Write for Serial<USART, TXPIN, RXPIN> {
     fn write_str(&mut self, s: &str) -> fmt::Result {
         s.as_bytes()
             .iter()
@@ -347,7 +836,7 @@ impl<USART: Deref<Target = usart0::RegisterBlock>, TXPIN: TxPin<USART>, RXPIN> f
     }
 }
 
-impl<USART: Deref<Target = usart0::RegisterBlock>> fmt::Write for Tx<USART> {
+impl<USART: Instance> fmt::Write for Tx<USART> {
     fn write_str(&mut self, s: &str) -> fmt::Result {
         s.as_bytes()
             .iter()
@@ -357,154 +846,1277 @@ impl<USART: Deref<Target = usart0::RegisterBlock>> fmt::Write for Tx<USART> {
 }
 
 // Implement reading trait if the USART has an RX pin assigned.
-impl<USART: Deref<Target = usart0::RegisterBlock>, TXPIN, RXPIN: RxPin<USART>> Read<u8>
-    for Serial<USART, TXPIN, RXPIN>
-{
+impl<USART: Instance, TXPIN, RXPIN: RxPin<USART>> Read<u8> for Serial<USART, TXPIN, RXPIN> {
     type Error = Error;
 
     fn read(&mut self) -> nb::Result<u8, Error> {
-        self.usart.read()
+        self.usart.registers().read().map(|word| word as u8)
     }
 }
 
-impl<USART: Deref<Target = usart0::RegisterBlock>> Read<u8> for Rx<USART> {
+impl<USART: Instance, TXPIN, RXPIN: RxPin<USART>> Read<u16> for Serial<USART, TXPIN, RXPIN> {
     type Error = Error;
 
-    fn read(&mut self) -> nb::Result<u8, Error> {
-        unsafe { &*self.usart }.read()
+    fn read(&mut self) -> nb::Result<u16, Error> {
+        self.usart.registers().read()
     }
 }
 
-trait UsartConfigExt {
-    type Bus;
+impl<USART: Instance> Read<u8> for Rx<USART> {
+    type Error = Error;
 
-    fn enable_configure(&self, config: Config, clocks: Clocks, bus: &mut Self::Bus);
+    fn read(&mut self) -> nb::Result<u8, Error> {
+        unsafe { &*self.usart }.read().map(|word| word as u8)
+    }
 }
 
-impl<USART: RcuBus + Enable + Reset + Deref<Target = usart0::RegisterBlock>> UsartConfigExt
-    for USART
-where
-    USART::Bus: GetBusFreq,
-{
-    type Bus = USART::Bus;
+impl<USART: Instance> Read<u16> for Rx<USART> {
+    type Error = Error;
 
-    /// Enable, reset and configure the USART.
-    fn enable_configure(&self, config: Config, clocks: Clocks, bus: &mut Self::Bus) {
-        // Enable clock for USART, and reset it.
-        USART::enable(bus);
-        USART::reset(bus);
+    fn read(&mut self) -> nb::Result<u16, Error> {
+        unsafe { &*self.usart }.read()
+    }
+}
 
-        // Configure baud rate.
-        let baud_rate_ratio = <USART as RcuBus>::Bus::get_frequency(&clocks).0 / config.baudrate.0;
-        assert!((16..=0xFFFF).contains(&baud_rate_ratio));
-        self.baud.write(|w| unsafe { w.bits(baud_rate_ratio) });
+/// RS-485 driver enable control for a serial transmitter, [`Tx`] or a TX DMA.
+///
+/// The driver enable pin is asserted (set high) before transmitting, and deasserted once the
+/// transmission is complete, as indicated by the TC flag. By default this is checked when flushing
+/// or waiting for a DMA transfer. After calling `listen`, it is instead done from the USART
+/// interrupt handler by [`Rs485::on_interrupt`], so that the bus is released as soon as possible.
+pub struct Rs485<TX, DE> {
+    tx: TX,
+    de: DE,
+    usart: *const UsartRegisterBlock,
+    asserted: bool,
+    interrupt: bool,
+}
 
-        // Configure parity. Note that the parity bit counts towards the word length, so we have to
-        // increase it to 9 bits if parity is enabled so as to still get 8 data bits.
-        match config.parity {
-            Parity::ParityNone => {
-                self.ctl0.modify(|_, w| w.pcen().disabled().wl().bit8());
-            }
-            Parity::ParityEven => {
-                self.ctl0
-                    .modify(|_, w| w.pcen().enabled().wl().bit9().pm().even());
-            }
-            Parity::ParityOdd => {
-                self.ctl0
-                    .modify(|_, w| w.pcen().enabled().wl().bit9().pm().odd());
-            }
-        }
+unsafe impl<TX: Send, DE: Send> Send for Rs485<TX, DE> {}
 
-        // Configure stop bits.
-        self.ctl1.modify(|_, w| w.stb().variant(config.stopbits));
+impl<USART: Instance, DE: OutputPin<Error = Infallible>> Rs485<Tx<USART>, DE> {
+    pub fn new(tx: Tx<USART>, mut de: DE) -> Self {
+        de.set_low().unwrap();
+        Self {
+            usart: tx.usart,
+            tx,
+            de,
+            asserted: false,
+            interrupt: false,
+        }
     }
 }
 
-trait UsartReadWrite {
-    fn read(&mut self) -> nb::Result<u8, Error>;
-    fn flush(&mut self) -> nb::Result<(), Infallible>;
-    fn write(&mut self, byte: u8) -> nb::Result<(), Infallible>;
-}
+impl<TX, DE: OutputPin<Error = Infallible>> Rs485<TX, DE> {
+    pub fn release(self) -> (TX, DE) {
+        (self.tx, self.de)
+    }
 
-impl<USART: Deref<Target = usart0::RegisterBlock>> UsartReadWrite for USART {
-    fn read(&mut self) -> nb::Result<u8, Error> {
-        let status = self.stat0.read();
+    /// Deasserts the driver enable from the USART interrupt handler, using the TC interrupt.
+    pub fn listen(&mut self) {
+        self.interrupt = true;
+    }
 
-        if status.perr().bit_is_set() {
-            self.data.read().data().bits();
-            Err(nb::Error::Other(Error::Parity))
-        } else if status.ferr().bit_is_set() {
-            self.data.read().data().bits();
-            Err(nb::Error::Other(Error::Framing))
-        } else if status.nerr().bit_is_set() {
-            self.data.read().data().bits();
-            Err(nb::Error::Other(Error::Noise))
-        } else if status.orerr().bit_is_set() {
-            self.data.read().data().bits();
-            Err(nb::Error::Other(Error::Overrun))
-        } else if status.rbne().bit_is_set() {
-            Ok(self.data.read().data().bits() as u8)
-        } else {
-            Err(nb::Error::WouldBlock)
+    /// Deasserts the driver enable when flushing, rather than from the interrupt handler.
+    pub fn unlisten(&mut self) {
+        self.interrupt = false;
+        unsafe { &*self.usart }
+            .ctl0
+            .modify(|_, w| w.tcie().disabled());
+    }
+
+    /// Deasserts the driver enable if the transmission is complete. This must be called from the
+    /// USART interrupt handler after `listen` has been called.
+    pub fn on_interrupt(&mut self) {
+        if self.deassert_if_complete() {
+            unsafe { &*self.usart }
+                .ctl0
+                .modify(|_, w| w.tcie().disabled());
         }
     }
 
-    fn flush(&mut self) -> nb::Result<(), Infallible> {
-        let status = self.stat0.read();
-        if status.tc().bit_is_set() {
-            Ok(())
-        } else {
-            Err(nb::Error::WouldBlock)
+    fn assert_driver(&mut self) {
+        if !self.asserted {
+            self.de.set_high().unwrap();
+            self.asserted = true;
         }
     }
 
-    fn write(&mut self, byte: u8) -> nb::Result<(), Infallible> {
-        let status = self.stat0.read();
-        if status.tbe().bit_is_set() {
-            self.data.write(|w| unsafe { w.data().bits(byte.into()) });
+    /// Enables the TC interrupt if needed. This must only be done once TC has been cleared by
+    /// starting a transmission.
+    fn listen_complete(&mut self) {
+        if self.interrupt {
+            unsafe { &*self.usart }
+                .ctl0
+                .modify(|_, w| w.tcie().enabled());
+        }
+    }
+
+    /// Deasserts the driver enable if the TC flag is set, and returns whether it is deasserted.
+    fn deassert_if_complete(&mut self) -> bool {
+        if self.asserted && unsafe { &*self.usart }.stat0.read().tc().bit_is_set() {
+            self.de.set_low().unwrap();
+            self.asserted = false;
+        }
+        !self.asserted
+    }
+}
+
+impl<USART: Instance, DE: OutputPin<Error = Infallible>> Write<u8> for Rs485<Tx<USART>, DE> {
+    type Error = Infallible;
+
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        if self.deassert_if_complete() {
             Ok(())
         } else {
             Err(nb::Error::WouldBlock)
         }
     }
+
+    fn write(&mut self, byte: u8) -> nb::Result<(), Self::Error> {
+        self.assert_driver();
+        Write::<u8>::write(&mut self.tx, byte)?;
+        self.listen_complete();
+        Ok(())
+    }
 }
 
-macro_rules! serialdma {
-    ($(
-        $USARTX:ty: (
-            $RxDmaX:ident,
-            $TxDmaX:ident,
-            $dmarxch:ty,
-            $dmatxch:ty,
-        ),
-    )+) => {
-        $(
-            pub type $RxDmaX = RxDma<Rx<$USARTX>, $dmarxch>;
-            pub type $TxDmaX = TxDma<Tx<$USARTX>, $dmatxch>;
+impl<USART: Instance, DE: OutputPin<Error = Infallible>> fmt::Write for Rs485<Tx<USART>, DE> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        s.as_bytes()
+            .iter()
+            .try_for_each(|c| nb::block!(self.write(*c)))
+            .map_err(|_| core::fmt::Error)
+    }
+}
 
-            impl Receive for $RxDmaX {
-                type RxChannel = $dmarxch;
-                type TransmittedWord = u8;
-            }
+/// Serial abstraction for half-duplex communication over a single wire.
+///
+/// The TX pin is used for both transmitting and receiving, so it must be configured as an
+/// open-drain alternate function output, and the line needs a pull-up resistor.
+///
+/// As the receiver is connected to the same line, everything transmitted is also received. If
+/// `set_discard_echo(true)` is called then this echo is discarded automatically, with each write
+/// waiting for the echo of the previous word so that it can't overrun the receiver.
+pub struct HalfDuplex<USART, PIN> {
+    usart: USART,
+    pin: PIN,
+    discard_echo: bool,
+    pending_echo: bool,
+}
 
-            impl Transmit for $TxDmaX {
-                type TxChannel = $dmatxch;
-                type ReceivedWord = u8;
-            }
+impl<USART: Instance, const P: char, const N: u8> HalfDuplex<USART, Pin<P, N, Alternate<OpenDrain>>>
+where
+    USART::Bus: GetBusFreq,
+    Pin<P, N, Alternate<OpenDrain>>: TxPin<USART>,
+{
+    /// Configures the USART in half-duplex mode and creates a new HalfDuplex instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is rejected, see [`HalfDuplex::try_usart`].
+    pub fn usart(
+        usart: USART,
+        pin: Pin<P, N, Alternate<OpenDrain>>,
+        config: Config,
+        clocks: Clocks,
+        bus: &mut USART::Bus,
+    ) -> Self {
+        Self::try_usart(usart, pin, config, clocks, bus).unwrap().0
+    }
 
-            impl TransferPayload for $RxDmaX {
-                fn start(&mut self) {
-                    self.channel.start();
-                }
+    /// Configures the USART in half-duplex mode and creates a new HalfDuplex instance, returning
+    /// it along with the achieved baud rate.
+    pub fn try_usart(
+        usart: USART,
+        pin: Pin<P, N, Alternate<OpenDrain>>,
+        config: Config,
+        clocks: Clocks,
+        bus: &mut USART::Bus,
+    ) -> Result<(Self, BaudRate), ConfigError> {
+        let baud_rate = usart.enable_configure(config, FlowControlPins::NONE, clocks, bus)?;
 
-                fn stop(&mut self) {
-                    self.channel.stop();
-                }
-            }
+        // Enable half-duplex mode, then the transmitter, receiver and the USART as a whole.
+        let registers = usart.registers();
+        registers.ctl2.modify(|_, w| w.hden().selected());
+        registers
+            .ctl0
+            .modify(|_, w| w.ten().enabled().ren().enabled().uen().enabled());
 
-            impl TransferPayload for $TxDmaX {
-                fn start(&mut self) {
-                    self.channel.start();
+        Ok((
+            Self {
+                usart,
+                pin,
+                discard_echo: false,
+                pending_echo: false,
+            },
+            baud_rate,
+        ))
+    }
+}
+
+impl<USART: Instance, PIN> HalfDuplex<USART, PIN> {
+    pub fn release(self) -> (USART, PIN) {
+        (self.usart, self.pin)
+    }
+
+    /// Sets whether words received while transmitting should be discarded.
+    pub fn set_discard_echo(&mut self, discard_echo: bool) {
+        self.discard_echo = discard_echo;
+        self.pending_echo = false;
+    }
+
+    /// Discards the echo of the last word written, if it hasn't been received yet.
+    fn discard_pending_echo(&mut self) -> nb::Result<(), Error> {
+        if self.pending_echo {
+            let echo = self.usart.registers().read();
+            if echo == Err(nb::Error::WouldBlock) {
+                return Err(nb::Error::WouldBlock);
+            }
+            self.pending_echo = false;
+            echo?;
+        }
+        Ok(())
+    }
+
+    fn read_word(&mut self) -> nb::Result<u16, Error> {
+        self.discard_pending_echo()?;
+        self.usart.registers().read()
+    }
+
+    fn write_word(&mut self, word: u16) -> nb::Result<(), Infallible> {
+        // Errors in the echo are of no interest, as the word has already been sent.
+        if self.discard_pending_echo() == Err(nb::Error::WouldBlock) {
+            return Err(nb::Error::WouldBlock);
+        }
+        self.usart.registers().write(word)?;
+        self.pending_echo = self.discard_echo;
+        Ok(())
+    }
+
+    fn flush_words(&mut self) -> nb::Result<(), Infallible> {
+        self.usart.registers().flush()?;
+        if self.discard_pending_echo() == Err(nb::Error::WouldBlock) {
+            return Err(nb::Error::WouldBlock);
+        }
+        Ok(())
+    }
+}
+
+impl<USART: Instance, PIN> Write<u8> for HalfDuplex<USART, PIN> {
+    type Error = Infallible;
+
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        self.flush_words()
+    }
+
+    fn write(&mut self, byte: u8) -> nb::Result<(), Self::Error> {
+        self.write_word(byte.into())
+    }
+}
+
+impl<USART: Instance, PIN> Write<u16> for HalfDuplex<USART, PIN> {
+    type Error = Infallible;
+
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        self.flush_words()
+    }
+
+    fn write(&mut self, word: u16) -> nb::Result<(), Self::Error> {
+        self.write_word(word)
+    }
+}
+
+impl<USART: Instance, PIN> fmt::Write for HalfDuplex<USART, PIN> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        s.as_bytes()
+            .iter()
+            .try_for_each(|c| nb::block!(self.write(*c)))
+            .map_err(|_| core::fmt::Error)
+    }
+}
+
+impl<USART: Instance, PIN> Read<u8> for HalfDuplex<USART, PIN> {
+    type Error = Error;
+
+    fn read(&mut self) -> nb::Result<u8, Error> {
+        self.read_word().map(|word| word as u8)
+    }
+}
+
+impl<USART: Instance, PIN> Read<u16> for HalfDuplex<USART, PIN> {
+    type Error = Error;
+
+    fn read(&mut self) -> nb::Result<u16, Error> {
+        self.read_word()
+    }
+}
+
+/// Serial abstraction for LIN mode.
+///
+/// The USART must be configured with 8 data bits and 1 stop bit, as required by LIN. A break
+/// received by the USART is reported as [`Error::Break`] rather than as a framing error. With
+/// 11-bit break detection the framing error may be detected before the break, in which case both
+/// errors are reported.
+pub struct Lin<USART, TXPIN, RXPIN> {
+    serial: Serial<USART, TXPIN, RXPIN>,
+}
+
+impl<USART: Instance, TXPIN, RXPIN> Serial<USART, TXPIN, RXPIN> {
+    /// Enables LIN mode, with the given break detection length.
+    pub fn into_lin(self, break_length: LinBreakLength) -> Lin<USART, TXPIN, RXPIN> {
+        let usart = self.usart.registers();
+        usart.ctl0.modify(|_, w| w.uen().disabled());
+        usart
+            .ctl1
+            .modify(|_, w| w.lmen().enabled().lblen().variant(break_length));
+        usart.ctl0.modify(|_, w| w.uen().enabled());
+        Lin { serial: self }
+    }
+}
+
+impl<USART: Instance, TXPIN, RXPIN> Lin<USART, TXPIN, RXPIN> {
+    /// Disables LIN mode.
+    pub fn into_serial(self) -> Serial<USART, TXPIN, RXPIN> {
+        let usart = self.serial.usart.registers();
+        usart.ctl0.modify(|_, w| w.uen().disabled());
+        usart.ctl1.modify(|_, w| w.lmen().disabled());
+        usart.ctl0.modify(|_, w| w.uen().enabled());
+        self.serial
+    }
+
+    /// Enable an interrupt event.
+    pub fn listen(&mut self, event: Event) {
+        self.serial.listen(event)
+    }
+
+    /// Disable an interrupt event.
+    pub fn unlisten(&mut self, event: Event) {
+        self.serial.unlisten(event)
+    }
+
+    /// Transmits a LIN break once the current transmission is complete.
+    pub fn send_break(&mut self) {
+        self.serial.send_break()
+    }
+
+    /// Checks for and clears the LIN break detection flag.
+    fn take_break(&mut self) -> bool {
+        let usart = self.serial.usart.registers();
+        let detected = usart.stat0.read().lbdf().bit_is_set();
+        if detected {
+            // Write 1 to the other flags so that any set since the read aren't cleared too.
+            usart.stat0.write(|w| {
+                w.rbne()
+                    .set_bit()
+                    .tc()
+                    .set_bit()
+                    .lbdf()
+                    .clear_bit()
+                    .ctsf()
+                    .set_bit()
+            });
+        }
+        detected
+    }
+}
+
+impl<USART: Instance, TXPIN: TxPin<USART>, RXPIN> Write<u8> for Lin<USART, TXPIN, RXPIN> {
+    type Error = Infallible;
+
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        self.serial.usart.registers().flush()
+    }
+
+    fn write(&mut self, byte: u8) -> nb::Result<(), Self::Error> {
+        self.serial.usart.registers().write(byte.into())
+    }
+}
+
+impl<USART: Instance, TXPIN: TxPin<USART>, RXPIN> fmt::Write for Lin<USART, TXPIN, RXPIN> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.serial.write_str(s)
+    }
+}
+
+impl<USART: Instance, TXPIN, RXPIN: RxPin<USART>> Read<u8> for Lin<USART, TXPIN, RXPIN> {
+    type Error = Error;
+
+    fn read(&mut self) -> nb::Result<u8, Error> {
+        if self.take_break() {
+            // The break is also received as a zero byte, which must be discarded.
+            self.serial.usart.registers().read().ok();
+            return Err(nb::Error::Other(Error::Break));
+        }
+        match self.serial.usart.registers().read() {
+            // Break detection may complete just after the framing error.
+            Err(nb::Error::Other(Error::Framing)) if self.take_break() => {
+                Err(nb::Error::Other(Error::Break))
+            }
+            result => result.map(|word| word as u8),
+        }
+    }
+}
+
+/// Configuration for smartcard mode.
+pub struct SmartcardConfig {
+    /// The frequency of the clock output to the card on the CK pin.
+    pub clock: Hertz,
+    /// The number of card clock cycles per elementary time unit (bit period), i.e. F/D.
+    pub etu: u16,
+    /// The guard time in bit periods, after which a transmission is considered complete.
+    pub guard_time: u8,
+    /// Whether to send a NACK when a character is received with a parity error.
+    pub nack: bool,
+    /// The number of times a character is retransmitted if the card NACKs it, up to 7.
+    pub retries: u8,
+}
+
+impl SmartcardConfig {
+    pub fn clock(mut self, clock: impl Into<Hertz>) -> Self {
+        self.clock = clock.into();
+        self
+    }
+
+    pub fn etu(mut self, etu: u16) -> Self {
+        self.etu = etu;
+        self
+    }
+
+    pub fn guard_time(mut self, guard_time: u8) -> Self {
+        self.guard_time = guard_time;
+        self
+    }
+
+    pub fn nack(mut self, nack: bool) -> Self {
+        self.nack = nack;
+        self
+    }
+
+    pub fn retries(mut self, retries: u8) -> Self {
+        self.retries = retries;
+        self
+    }
+}
+
+impl Default for SmartcardConfig {
+    /// A 4 MHz clock with the ISO 7816-3 default F/D of 372, as used until the card negotiates
+    /// otherwise.
+    fn default() -> SmartcardConfig {
+        SmartcardConfig {
+            clock: 4_000_000.hz(),
+            etu: 372,
+            guard_time: 2,
+            nack: true,
+            retries: 3,
+        }
+    }
+}
+
+/// The maximum length of a smartcard's answer to reset, including TS.
+pub const MAX_ATR_LENGTH: usize = 33;
+
+/// Serial abstraction for ISO 7816-3 smartcard mode. This is only available on USART0, USART1 and
+/// USART2.
+///
+/// The TX pin is used as the bidirectional I/O line, so it must be configured as an open-drain
+/// alternate function output, and the line needs a pull-up resistor. The card clock is output on
+/// the CK pin. The card's reset and power lines are not handled here and should be driven with
+/// normal GPIOs.
+///
+/// Frames are 8 data bits with even parity and 1.5 stop bits. As the receiver shares the line with
+/// the transmitter, the echo of transmitted characters is discarded automatically when reading. A
+/// character which the card still NACKs after all retransmissions is reported as a framing error
+/// when reading.
+pub struct Smartcard<USART, IOPIN, CKPIN> {
+    half_duplex: HalfDuplex<USART, IOPIN>,
+    ck: CKPIN,
+}
+
+impl<USART: Instance, const P: char, const N: u8, CKPIN: CkPin<USART>>
+    Smartcard<USART, Pin<P, N, Alternate<OpenDrain>>, CKPIN>
+where
+    USART::Bus: GetBusFreq,
+    Pin<P, N, Alternate<OpenDrain>>: TxPin<USART>,
+{
+    /// Configures the USART in smartcard mode and creates a new Smartcard instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is rejected, see [`Smartcard::try_usart`].
+    pub fn usart(
+        usart: USART,
+        pins: (Pin<P, N, Alternate<OpenDrain>>, CKPIN),
+        config: SmartcardConfig,
+        clocks: Clocks,
+        bus: &mut USART::Bus,
+    ) -> Self {
+        Self::try_usart(usart, pins, config, clocks, bus).unwrap().0
+    }
+
+    /// Configures the USART in smartcard mode and creates a new Smartcard instance, returning it
+    /// along with the achieved baud rate.
+    pub fn try_usart(
+        usart: USART,
+        pins: (Pin<P, N, Alternate<OpenDrain>>, CKPIN),
+        config: SmartcardConfig,
+        clocks: Clocks,
+        bus: &mut USART::Bus,
+    ) -> Result<(Self, BaudRate), ConfigError> {
+        // The card clock is the bus clock divided by twice the prescaler, and the bit period is a
+        // whole number of card clock cycles.
+        let pclk = <USART as RcuBus>::Bus::get_frequency(&clocks).0;
+        let prescaler = (pclk + config.clock.0) / (2 * config.clock.0);
+        if !(1..=0x1F).contains(&prescaler) {
+            return Err(ConfigError::SmartcardClockOutOfRange);
+        }
+        if config.retries > 7 {
+            return Err(ConfigError::SmartcardRetriesOutOfRange);
+        }
+        let baudrate = pclk / (2 * prescaler * u32::from(config.etu));
+
+        let baud_rate = usart.enable_configure(
+            Config::default()
+                .baudrate(baudrate.bps())
+                .parity_even()
+                .stopbits(StopBits::STOP1P5),
+            FlowControlPins::NONE,
+            clocks,
+            bus,
+        )?;
+
+        let registers = usart.registers();
+        registers
+            .gp
+            .modify(|_, w| w.psc().bits(prescaler as u8).guat().bits(config.guard_time));
+        registers.ctl1.modify(|_, w| w.cken().enabled());
+        registers
+            .ctl3
+            .modify(|_, w| unsafe { w.scrtnum().bits(config.retries) });
+        registers
+            .ctl2
+            .modify(|_, w| w.scen().enabled().nken().bit(config.nack));
+        registers
+            .ctl0
+            .modify(|_, w| w.ten().enabled().ren().enabled().uen().enabled());
+
+        let (pin, ck) = pins;
+        Ok((
+            Self {
+                half_duplex: HalfDuplex {
+                    usart,
+                    pin,
+                    discard_echo: true,
+                    pending_echo: false,
+                },
+                ck,
+            },
+            baud_rate,
+        ))
+    }
+}
+
+impl<USART: Instance, IOPIN, CKPIN> Smartcard<USART, IOPIN, CKPIN> {
+    pub fn release(self) -> (USART, (IOPIN, CKPIN)) {
+        let (usart, pin) = self.half_duplex.release();
+        (usart, (pin, self.ck))
+    }
+
+    /// Reads the answer to reset sent by the card, blocking until it is complete.
+    ///
+    /// This should be called just after releasing the card's reset line. The length of the answer
+    /// is determined from its interface bytes. If TS indicates the inverse convention then the
+    /// USART is switched to it, so that it is used for all further communication. TS is returned
+    /// as sent by the card in either case.
+    pub fn read_atr<'a>(
+        &mut self,
+        buffer: &'a mut [u8; MAX_ATR_LENGTH],
+    ) -> Result<&'a [u8], Error> {
+        // With the direct convention, the inverse convention TS 0x3F is received as 0x03.
+        buffer[0] = match nb::block!(self.read())? {
+            0x3B => 0x3B,
+            0x03 => {
+                self.set_inverse_convention();
+                0x3F
+            }
+            _ => return Err(Error::InvalidAtr),
+        };
+        let mut length = 1;
+        let mut next = |this: &mut Self| -> Result<u8, Error> {
+            let byte = buffer.get_mut(length).ok_or(Error::InvalidAtr)?;
+            *byte = nb::block!(this.read())?;
+            length += 1;
+            Ok(*byte)
+        };
+
+        // T0 and each TDi indicate which of the following TA, TB, TC and TD bytes are present. A
+        // check byte TCK follows the historical bytes if any protocol other than T=0 is offered.
+        let format = next(self)?;
+        let historical_bytes = format & 0x0F;
+        let mut indicator = format >> 4;
+        let mut check_byte = false;
+        loop {
+            for _ in 0..(indicator & 0x07).count_ones() {
+                next(self)?;
+            }
+            if indicator & 0x08 == 0 {
+                break;
+            }
+            let td = next(self)?;
+            check_byte |= td & 0x0F != 0;
+            indicator = td >> 4;
+        }
+        for _ in 0..historical_bytes {
+            next(self)?;
+        }
+        if check_byte {
+            next(self)?;
+        }
+
+        Ok(&buffer[..length])
+    }
+
+    /// Switches the USART to the inverse convention, with inverted data bits sent MSB first.
+    fn set_inverse_convention(&mut self) {
+        let usart = self.half_duplex.usart.registers();
+        usart.ctl0.modify(|_, w| w.uen().disabled());
+        usart
+            .ctl3
+            .modify(|_, w| w.dinv().set_bit().msbf().set_bit());
+        usart.ctl0.modify(|_, w| w.uen().enabled());
+    }
+}
+
+impl<USART: Instance, IOPIN, CKPIN> Write<u8> for Smartcard<USART, IOPIN, CKPIN> {
+    type Error = Infallible;
+
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        Write::<u8>::flush(&mut self.half_duplex)
+    }
+
+    fn write(&mut self, byte: u8) -> nb::Result<(), Self::Error> {
+        self.half_duplex.write_word(byte.into())
+    }
+}
+
+impl<USART: Instance, IOPIN, CKPIN> Read<u8> for Smartcard<USART, IOPIN, CKPIN> {
+    type Error = Error;
+
+    fn read(&mut self) -> nb::Result<u8, Error> {
+        self.half_duplex.read_word().map(|word| word as u8)
+    }
+}
+
+/// Configuration for synchronous mode.
+pub struct SynchronousConfig {
+    pub baudrate: Bps,
+    /// The clock polarity and phase.
+    pub mode: Mode,
+    /// Whether the clock pulse for the last data bit is output on the CK pin. Most SPI devices need
+    /// this.
+    pub last_bit_clock_pulse: bool,
+    /// Whether the most significant bit is sent first, as in SPI, rather than the least
+    /// significant bit as in asynchronous mode.
+    pub msb_first: bool,
+}
+
+impl SynchronousConfig {
+    pub fn baudrate(mut self, baudrate: Bps) -> Self {
+        self.baudrate = baudrate;
+        self
+    }
+
+    pub fn mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn last_bit_clock_pulse(mut self, last_bit_clock_pulse: bool) -> Self {
+        self.last_bit_clock_pulse = last_bit_clock_pulse;
+        self
+    }
+
+    pub fn msb_first(mut self, msb_first: bool) -> Self {
+        self.msb_first = msb_first;
+        self
+    }
+}
+
+impl Default for SynchronousConfig {
+    fn default() -> SynchronousConfig {
+        SynchronousConfig {
+            baudrate: 1_000_000_u32.bps(),
+            mode: spi::MODE_0,
+            last_bit_clock_pulse: true,
+            msb_first: true,
+        }
+    }
+}
+
+/// Synchronous serial abstraction, acting as an SPI master. This is only available on USART0,
+/// USART1 and USART2.
+///
+/// The TX pin is used as MOSI, the RX pin as MISO and the CK pin as SCK. The clock is only output
+/// while transmitting, so a word must be sent for each word to be received. There is no chip select,
+/// so if needed it must be driven with a normal GPIO.
+pub struct Synchronous<USART, PINS> {
+    usart: USART,
+    pins: PINS,
+}
+
+impl<USART: Instance, TXPIN: TxPin<USART>, RXPIN: RxPin<USART>, CKPIN: CkPin<USART>>
+    Synchronous<USART, (TXPIN, RXPIN, CKPIN)>
+where
+    USART::Bus: GetBusFreq,
+{
+    /// Configures the USART in synchronous mode and creates a new Synchronous instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is rejected, see [`Synchronous::try_usart`].
+    pub fn usart(
+        usart: USART,
+        pins: (TXPIN, RXPIN, CKPIN),
+        config: SynchronousConfig,
+        clocks: Clocks,
+        bus: &mut USART::Bus,
+    ) -> Self {
+        Self::try_usart(usart, pins, config, clocks, bus).unwrap().0
+    }
+
+    /// Configures the USART in synchronous mode and creates a new Synchronous instance, returning
+    /// it along with the achieved baud rate.
+    pub fn try_usart(
+        usart: USART,
+        pins: (TXPIN, RXPIN, CKPIN),
+        config: SynchronousConfig,
+        clocks: Clocks,
+        bus: &mut USART::Bus,
+    ) -> Result<(Self, BaudRate), ConfigError> {
+        let baud_rate = usart.enable_configure(
+            Config::default().baudrate(config.baudrate),
+            FlowControlPins::NONE,
+            clocks,
+            bus,
+        )?;
+
+        // Enable the clock output, then the transmitter, receiver and the USART as a whole.
+        let registers = usart.registers();
+        registers.ctl1.modify(|_, w| {
+            w.cken().enabled();
+            match config.mode.polarity {
+                Polarity::IdleLow => w.cpl().not_inverted(),
+                Polarity::IdleHigh => w.cpl().inverted(),
+            };
+            match config.mode.phase {
+                Phase::CaptureOnFirstTransition => w.cph().first(),
+                Phase::CaptureOnSecondTransition => w.cph().second(),
+            };
+            if config.last_bit_clock_pulse {
+                w.clen().output()
+            } else {
+                w.clen().not_output()
+            }
+        });
+        registers.ctl3.modify(|_, w| w.msbf().bit(config.msb_first));
+        registers
+            .ctl0
+            .modify(|_, w| w.ten().enabled().ren().enabled().uen().enabled());
+
+        Ok((Self { usart, pins }, baud_rate))
+    }
+}
+
+impl<USART: Instance, PINS> Synchronous<USART, PINS> {
+    pub fn release(self) -> (USART, PINS) {
+        (self.usart, self.pins)
+    }
+}
+
+impl<USART: Instance, PINS> spi::FullDuplex<u8> for Synchronous<USART, PINS> {
+    type Error = Error;
+
+    fn read(&mut self) -> nb::Result<u8, Error> {
+        self.usart.registers().read().map(|word| word as u8)
+    }
+
+    fn send(&mut self, byte: u8) -> nb::Result<(), Error> {
+        self.usart
+            .registers()
+            .write(byte.into())
+            .map_err(|error| match error {
+                nb::Error::WouldBlock => nb::Error::WouldBlock,
+                nb::Error::Other(infallible) => match infallible {},
+            })
+    }
+}
+
+impl<USART: Instance, PINS> embedded_hal::blocking::spi::transfer::Default<u8>
+    for Synchronous<USART, PINS>
+{
+}
+
+impl<USART: Instance, PINS> embedded_hal::blocking::spi::write::Default<u8>
+    for Synchronous<USART, PINS>
+{
+}
+
+impl<USART: Instance, PINS> embedded_hal::blocking::spi::write_iter::Default<u8>
+    for Synchronous<USART, PINS>
+{
+}
+
+/// Measures the bit period of a 0x55 or 0x7F sync byte from the edges of the RX pin captured on the
+/// channel, which must be set to capture falling edges, and waits for the end of its stop bit.
+///
+/// Both sync bytes start with a low pulse of 1 bit for the start bit, followed by a high pulse of 1
+/// bit for 0x55 or 7 bits for 0x7F, and another low pulse of 1 bit.
+fn measure_sync_byte<RB: TimerRegExt>(
+    capture: &RB,
+    channel: pwm::Channel,
+    timeout_ticks: u64,
+) -> Result<u32, ConfigError> {
+    let start =
+        wait_for_capture(capture, channel, timeout_ticks).ok_or(ConfigError::AutobaudTimeout)?;
+    capture.set_polarity(channel, false, pwm::Polarity::NotInverted);
+    let end = wait_for_capture(capture, channel, u64::from(u16::MAX))
+        .ok_or(ConfigError::BaudRateOutOfRange)?;
+    let bit_ticks = u32::from(end.wrapping_sub(start));
+    if bit_ticks == 0 || 15 * bit_ticks / 2 > u32::from(u16::MAX) {
+        return Err(ConfigError::BaudRateOutOfRange);
+    }
+
+    capture.set_polarity(channel, false, pwm::Polarity::Inverted);
+    let next_start = wait_for_capture(capture, channel, u64::from(15 * bit_ticks / 2))
+        .ok_or(ConfigError::AutobaudInvalidSync)?;
+    capture.set_polarity(channel, false, pwm::Polarity::NotInverted);
+    let next_end = wait_for_capture(capture, channel, u64::from(3 * bit_ticks / 2))
+        .ok_or(ConfigError::AutobaudInvalidSync)?;
+
+    // Allow each pulse to be up to half a bit period off.
+    let is_bits =
+        |ticks: u16, bits: u32| u32::from(ticks).abs_diff(bits * bit_ticks) < bit_ticks / 2;
+    let high_ticks = next_start.wrapping_sub(end);
+    let high_bits = if is_bits(high_ticks, 1) {
+        1
+    } else if is_bits(high_ticks, 7) {
+        7
+    } else {
+        return Err(ConfigError::AutobaudInvalidSync);
+    };
+    if !is_bits(next_end.wrapping_sub(next_start), 1) {
+        return Err(ConfigError::AutobaudInvalidSync);
+    }
+
+    // Wait for the rest of the sync byte, including the stop bit.
+    let mut last = next_end;
+    let mut elapsed = 0;
+    while elapsed < (8 - high_bits) * bit_ticks {
+        let now = capture.get_counter();
+        elapsed += u32::from(now.wrapping_sub(last));
+        last = now;
+    }
+    Ok(bit_ticks)
+}
+
+/// Waits for an edge to be captured on the channel for up to `limit` timer ticks, and returns the
+/// captured counter value.
+fn wait_for_capture<RB: TimerRegExt>(
+    capture: &RB,
+    channel: pwm::Channel,
+    limit: u64,
+) -> Option<u16> {
+    let mut last = capture.get_counter();
+    let mut elapsed = 0;
+    while !capture.is_captured(channel) {
+        let now = capture.get_counter();
+        elapsed += u64::from(now.wrapping_sub(last));
+        last = now;
+        if elapsed > limit {
+            return None;
+        }
+    }
+    Some(capture.get_duty(channel))
+}
+
+/// The nominal frequency of the prescaled clock for IrDA low-power mode.
+const IRDA_LOW_POWER_FREQUENCY: u32 = 1_843_200;
+
+trait UsartConfigExt {
+    type Bus;
+
+    fn enable_configure(
+        &self,
+        config: Config,
+        flow_control_pins: FlowControlPins,
+        clocks: Clocks,
+        bus: &mut Self::Bus,
+    ) -> Result<BaudRate, ConfigError>;
+}
+
+impl<USART: Instance> UsartConfigExt for USART
+where
+    USART::Bus: GetBusFreq,
+{
+    type Bus = USART::Bus;
+
+    /// Enable, reset and configure the USART.
+    fn enable_configure(
+        &self,
+        config: Config,
+        flow_control_pins: FlowControlPins,
+        clocks: Clocks,
+        bus: &mut Self::Bus,
+    ) -> Result<BaudRate, ConfigError> {
+        let pclk = <USART as RcuBus>::Bus::get_frequency(&clocks).0;
+        let (checked, baud_rate) = check_config(pclk, &config, flow_control_pins)?;
+
+        // Enable clock for USART, and reset it.
+        USART::enable(bus);
+        USART::reset(bus);
+
+        configure(self.registers(), config, checked);
+        Ok(baud_rate)
+    }
+}
+
+/// Reconfigures an enabled USART, once any ongoing transmission is complete.
+fn reconfigure<USART: Instance>(
+    usart: &UsartRegisterBlock,
+    config: Config,
+    flow_control_pins: FlowControlPins,
+    clocks: &Clocks,
+) -> Result<BaudRate, ConfigError>
+where
+    USART::Bus: GetBusFreq,
+{
+    let pclk = <USART as RcuBus>::Bus::get_frequency(clocks).0;
+    let (checked, baud_rate) = check_config(pclk, &config, flow_control_pins)?;
+
+    while usart.stat0.read().tc().bit_is_clear() {}
+    usart.ctl0.modify(|_, w| w.uen().disabled());
+    configure(usart, config, checked);
+    usart.ctl0.modify(|_, w| w.uen().enabled());
+    Ok(baud_rate)
+}
+
+/// Register values calculated from a configuration which has been checked.
+struct CheckedConfig {
+    baud_rate_ratio: u32,
+    irda_prescaler: u8,
+}
+
+/// Checks that the configuration is supported, so that the USART can be configured without
+/// failing part way through, and calculates the register values which depend on the bus clock.
+fn check_config(
+    pclk: u32,
+    config: &Config,
+    flow_control_pins: FlowControlPins,
+) -> Result<(CheckedConfig, BaudRate), ConfigError> {
+    if let (WordLength::DataBits9, Parity::ParityEven | Parity::ParityOdd) =
+        (&config.wordlength, &config.parity)
+    {
+        return Err(ConfigError::ParityWithNineDataBits);
+    }
+
+    let (rts, cts) = flow_control_bits(&config.flow_control);
+    if (rts && !flow_control_pins.rts) || (cts && !flow_control_pins.cts) {
+        return Err(ConfigError::FlowControlWithoutPin);
+    }
+
+    // The prescaler must be 1 in normal mode, and in low-power mode must give a frequency between
+    // 1.42 MHz and 2.12 MHz.
+    let irda_prescaler = match config.irda {
+        IrdaMode::Disabled | IrdaMode::Normal => 1,
+        IrdaMode::LowPower => {
+            let prescaler = (pclk + IRDA_LOW_POWER_FREQUENCY / 2) / IRDA_LOW_POWER_FREQUENCY;
+            if !(1..=0xFF).contains(&prescaler)
+                || !(1_420_000..=2_120_000).contains(&(pclk / prescaler))
+            {
+                return Err(ConfigError::IrdaPrescalerOutOfRange);
+            }
+            prescaler as u8
+        }
+    };
+
+    let (baud_rate_ratio, baud_rate) = baud_rate_ratio(pclk, config)?;
+    Ok((
+        CheckedConfig {
+            baud_rate_ratio,
+            irda_prescaler,
+        },
+        baud_rate,
+    ))
+}
+
+/// Returns whether RTS and CTS flow control are enabled by the given flow control mode.
+fn flow_control_bits(flow_control: &FlowControl) -> (bool, bool) {
+    match flow_control {
+        FlowControl::None => (false, false),
+        FlowControl::Rts => (true, false),
+        FlowControl::Cts => (false, true),
+        FlowControl::RtsCts => (true, true),
+    }
+}
+
+/// Calculates the value of the BAUD register for the configured baud rate, and checks that the
+/// achieved baud rate is within the configured tolerance.
+fn baud_rate_ratio(pclk: u32, config: &Config) -> Result<(u32, BaudRate), ConfigError> {
+    let requested = config.baudrate.0;
+    let baud_rate_ratio = (pclk + requested / 2) / requested;
+    if !(16..=0xFFFF).contains(&baud_rate_ratio) {
+        return Err(ConfigError::BaudRateOutOfRange);
+    }
+
+    let achieved = pclk / baud_rate_ratio;
+    let baud_rate = BaudRate {
+        achieved: achieved.bps(),
+        error_percent: (achieved as f32 - requested as f32) * 100.0 / requested as f32,
+    };
+    if baud_rate.error_percent.abs() > config.max_baudrate_error {
+        return Err(ConfigError::BaudRateInaccurate(baud_rate));
+    }
+    Ok((baud_rate_ratio, baud_rate))
+}
+
+/// Configures the USART, which must be disabled, with a configuration checked by [`check_config`].
+fn configure(usart: &UsartRegisterBlock, config: Config, checked: CheckedConfig) {
+    usart
+        .baud
+        .write(|w| unsafe { w.bits(checked.baud_rate_ratio) });
+
+    // Configure word length and parity. Note that the parity bit counts towards the word
+    // length, so we have to increase it to 9 bits if parity is enabled so as to still get 8
+    // data bits.
+    let parity_bits = match config.parity {
+        Parity::ParityNone => {
+            usart.ctl0.modify(|_, w| w.pcen().disabled());
+            0
+        }
+        Parity::ParityEven => {
+            usart.ctl0.modify(|_, w| w.pcen().enabled().pm().even());
+            1
+        }
+        Parity::ParityOdd => {
+            usart.ctl0.modify(|_, w| w.pcen().enabled().pm().odd());
+            1
+        }
+    };
+    let data_bits = match config.wordlength {
+        WordLength::DataBits8 => 8,
+        WordLength::DataBits9 => 9,
+    };
+    if data_bits + parity_bits == 8 {
+        usart.ctl0.modify(|_, w| w.wl().bit8());
+    } else {
+        usart.ctl0.modify(|_, w| w.wl().bit9());
+    }
+
+    // Configure stop bits.
+    usart.ctl1.modify(|_, w| w.stb().variant(config.stopbits));
+
+    // Configure hardware flow control.
+    let (rts, cts) = flow_control_bits(&config.flow_control);
+    usart
+        .ctl2
+        .modify(|_, w| w.rtsen().bit(rts).ctsen().bit(cts));
+
+    // Configure IrDA.
+    match config.irda {
+        IrdaMode::Disabled => usart.ctl2.modify(|_, w| w.iren().disabled()),
+        IrdaMode::Normal => {
+            usart.gp.modify(|_, w| w.psc().bits(checked.irda_prescaler));
+            usart.ctl2.modify(|_, w| w.iren().enabled().irlp().normal());
+        }
+        IrdaMode::LowPower => {
+            usart.gp.modify(|_, w| w.psc().bits(checked.irda_prescaler));
+            usart
+                .ctl2
+                .modify(|_, w| w.iren().enabled().irlp().low_power());
+        }
+    }
+}
+
+trait UsartReadWrite {
+    fn read(&self) -> nb::Result<u16, Error>;
+    fn flush(&self) -> nb::Result<(), Infallible>;
+    fn write(&self, word: u16) -> nb::Result<(), Infallible>;
+}
+
+impl UsartReadWrite for UsartRegisterBlock {
+    fn read(&self) -> nb::Result<u16, Error> {
+        let status = self.stat0.read();
+
+        if status.perr().bit_is_set() {
+            self.data.read().data().bits();
+            Err(nb::Error::Other(Error::Parity))
+        } else if status.ferr().bit_is_set() {
+            self.data.read().data().bits();
+            Err(nb::Error::Other(Error::Framing))
+        } else if status.nerr().bit_is_set() {
+            self.data.read().data().bits();
+            Err(nb::Error::Other(Error::Noise))
+        } else if status.orerr().bit_is_set() {
+            self.data.read().data().bits();
+            Err(nb::Error::Other(Error::Overrun))
+        } else if status.rbne().bit_is_set() {
+            Ok(self.data.read().data().bits())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+
+    fn flush(&self) -> nb::Result<(), Infallible> {
+        let status = self.stat0.read();
+        if status.tc().bit_is_set() {
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+
+    fn write(&self, word: u16) -> nb::Result<(), Infallible> {
+        let status = self.stat0.read();
+        if status.tbe().bit_is_set() {
+            self.data.write(|w| unsafe { w.data().bits(word) });
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+}
+
+/// DMA reception of variable-length packets terminated by an idle line, alternating between two
+/// buffers so that one can be processed while the other is being received into.
+pub struct IdleReceiver<RXDMA, const N: usize> {
+    buffers: &'static mut [[u8; N]; 2],
+    active: usize,
+    payload: RXDMA,
+}
+
+macro_rules! serialdma_word {
+    ($USARTX:ty, $RxDmaX:ident, $TxDmaX:ident, $word:ty, $width:expr) => {
+        impl<B> CircReadDma<B, $word> for $RxDmaX
+        where
+            &'static mut [B; 2]: WriteBuffer<Word = $word>,
+            B: 'static,
+        {
+            fn circ_read(mut self, mut buffer: &'static mut [B; 2]) -> CircBuffer<B, Self> {
+                // NOTE(unsafe) We own the buffer now and we won't call other `&mut` on it
+                // until the end of the transfer.
+                let (ptr, len) = unsafe { buffer.write_buffer() };
+                self.channel.set_peripheral_address(
+                    unsafe { &(*<$USARTX>::ptr()).data as *const _ as u32 },
+                    false,
+                );
+                self.channel.set_memory_address(ptr as u32, true);
+                self.channel.set_transfer_length(len);
+
+                atomic::compiler_fence(Ordering::Release);
+
+                self.channel
+                    .configure_from_peripheral(Priority::Medium, $width, $width, true);
+
+                self.start();
+
+                CircBuffer::new(buffer, self)
+            }
+        }
+
+        impl<B> ReadDma<B, $word> for $RxDmaX
+        where
+            B: WriteBuffer<Word = $word>,
+        {
+            fn read(mut self, mut buffer: B) -> Transfer<W, B, Self> {
+                // NOTE(unsafe) We own the buffer now and we won't call other `&mut` on it
+                // until the end of the transfer.
+                let (ptr, len) = unsafe { buffer.write_buffer() };
+                self.channel.set_peripheral_address(
+                    unsafe { &(*<$USARTX>::ptr()).data as *const _ as u32 },
+                    false,
+                );
+                self.channel.set_memory_address(ptr as u32, true);
+                self.channel.set_transfer_length(len);
+
+                atomic::compiler_fence(Ordering::Release);
+                self.channel
+                    .configure_from_peripheral(Priority::Medium, $width, $width, false);
+                self.start();
+
+                Transfer::w(buffer, self)
+            }
+        }
+
+        impl<B> WriteDma<B, $word> for $TxDmaX
+        where
+            B: ReadBuffer<Word = $word>,
+        {
+            fn write(mut self, buffer: B) -> Transfer<R, B, Self> {
+                // Clear transmission complete bit.
+                unsafe { &*self.payload.usart }
+                    .stat0
+                    .write(|w| w.tc().clear_bit());
+
+                // NOTE(unsafe) We own the buffer now and we won't call other `&mut` on it
+                // until the end of the transfer.
+                let (ptr, len) = unsafe { buffer.read_buffer() };
+
+                self.channel.set_peripheral_address(
+                    unsafe { &(*<$USARTX>::ptr()).data as *const _ as u32 },
+                    false,
+                );
+
+                self.channel.set_memory_address(ptr as u32, true);
+                self.channel.set_transfer_length(len);
+
+                atomic::compiler_fence(Ordering::Release);
+
+                self.channel
+                    .configure_to_peripheral(Priority::Medium, $width, $width, false);
+                self.start();
+
+                Transfer::r(buffer, self)
+            }
+        }
+    };
+}
+
+macro_rules! serialdma {
+    ($(
+        $USARTX:ty: (
+            $RxDmaX:ident,
+            $TxDmaX:ident,
+            $dmarxch:ty,
+            $dmatxch:ty,
+        ),
+    )+) => {
+        $(
+            pub type $RxDmaX = RxDma<Rx<$USARTX>, $dmarxch>;
+            pub type $TxDmaX = TxDma<Tx<$USARTX>, $dmatxch>;
+
+            impl Receive for $RxDmaX {
+                type RxChannel = $dmarxch;
+                type TransmittedWord = u8;
+            }
+
+            impl Transmit for $TxDmaX {
+                type TxChannel = $dmatxch;
+                type ReceivedWord = u8;
+            }
+
+            impl TransferPayload for $RxDmaX {
+                fn start(&mut self) {
+                    self.channel.start();
+                }
+
+                fn stop(&mut self) {
+                    self.channel.stop();
+                }
+            }
+
+            impl TransferPayload for $TxDmaX {
+                fn start(&mut self) {
+                    self.channel.start();
                 }
 
                 fn stop(&mut self) {
@@ -514,6 +2126,9 @@ macro_rules! serialdma {
 
             impl Rx<$USARTX> {
                 pub fn with_dma(self, channel: $dmarxch) -> $RxDmaX {
+                    unsafe { &*self.usart }
+                        .ctl2
+                        .modify(|_, w| w.denr().enabled());
                     RxDma {
                         payload: self,
                         channel,
@@ -523,6 +2138,9 @@ macro_rules! serialdma {
 
             impl Tx<$USARTX> {
                 pub fn with_dma(self, channel: $dmatxch) -> $TxDmaX {
+                    unsafe { &*self.usart }
+                        .ctl2
+                        .modify(|_, w| w.dent().enabled());
                     TxDma {
                         payload: self,
                         channel,
@@ -534,94 +2152,166 @@ macro_rules! serialdma {
                 pub fn split(mut self) -> (Rx<$USARTX>, $dmarxch) {
                     self.stop();
                     let RxDma { payload, channel } = self;
+                    unsafe { &*payload.usart }
+                        .ctl2
+                        .modify(|_, w| w.denr().disabled());
                     (payload, channel)
                 }
-            }
 
-            impl $TxDmaX {
-                pub fn split(mut self) -> (Tx<$USARTX>, $dmatxch) {
-                    self.stop();
-                    let TxDma { payload, channel } = self;
-                    (payload, channel)
+                /// Receives variable-length packets, each terminated by an idle line, into
+                /// alternate buffers. This enables the IDLE interrupt, and
+                /// [`IdleReceiver::on_idle`] should be called when it occurs.
+                pub fn read_until_idle<const N: usize>(
+                    mut self,
+                    buffers: &'static mut [[u8; N]; 2],
+                ) -> IdleReceiver<Self, N> {
+                    let usart = unsafe { &*self.payload.usart };
+                    self.channel
+                        .set_peripheral_address(&usart.data as *const _ as u32, false);
+                    self.channel
+                        .configure_from_peripheral(Priority::Medium, Width::Bits8, Width::Bits8, false);
+
+                    let mut receiver = IdleReceiver {
+                        buffers,
+                        active: 0,
+                        payload: self,
+                    };
+                    receiver.restart();
+                    usart.ctl0.modify(|_, w| w.idleie().enabled());
+                    receiver
                 }
             }
 
-            impl<B> CircReadDma<B, u8> for $RxDmaX
-            where
-                &'static mut [B; 2]: WriteBuffer<Word = u8>,
-                B: 'static,
-            {
-                fn circ_read(mut self, mut buffer: &'static mut [B; 2]) -> CircBuffer<B, Self> {
-                    // NOTE(unsafe) We own the buffer now and we won't call other `&mut` on it
-                    // until the end of the transfer.
-                    let (ptr, len) = unsafe { buffer.write_buffer() };
-                    self.channel
-                        .set_peripheral_address(unsafe { &(*<$USARTX>::ptr()).data as *const _ as u32 }, false);
-                    self.channel.set_memory_address(ptr as u32, true);
-                    self.channel.set_transfer_length(len);
-
-                    atomic::compiler_fence(Ordering::Release);
+            impl<const N: usize> IdleReceiver<$RxDmaX, N> {
+                /// If the line has gone idle, completes the current packet and starts receiving
+                /// into the other buffer. Returns the received packet, or `None` if the line has
+                /// not gone idle or nothing was received.
+                ///
+                /// A packet which is longer than the buffer is discarded, and reported as
+                /// [`Error::Overrun`].
+                pub fn on_idle(&mut self) -> Result<Option<&[u8]>, Error> {
+                    let usart = unsafe { &*self.payload.payload.usart };
+                    if usart.stat0.read().idlef().bit_is_clear() {
+                        return Ok(None);
+                    }
 
-                    self.channel
-                        .configure_from_peripheral(Priority::Medium, Width::Bits8, Width::Bits8, true);
+                    // Stop the DMA before clearing the flag, as that reads DATA.
+                    self.payload.channel.stop();
+                    atomic::compiler_fence(Ordering::Acquire);
+                    let received = N - usize::from(self.payload.channel.get_cnt());
+
+                    // The flag is cleared by reading STAT0 followed by DATA. If the buffer is full,
+                    // a word waiting in DATA is left over from the packet and is discarded. If not,
+                    // it is the start of the next packet, so is left for the DMA to read once it
+                    // restarts, which clears the flag too.
+                    let status = usart.stat0.read();
+                    let pending = status.rbne().bit_is_set();
+                    let truncated = status.orerr().bit_is_set() || (received == N && pending);
+                    if truncated || !pending {
+                        usart.data.read();
+                    }
 
-                    self.start();
+                    let completed = self.active;
+                    self.active = 1 - completed;
+                    self.restart();
 
-                    CircBuffer::new(buffer, self)
+                    if truncated {
+                        Err(Error::Overrun)
+                    } else if received == 0 {
+                        Ok(None)
+                    } else {
+                        Ok(Some(&self.buffers[completed][..received]))
+                    }
                 }
-            }
 
-            impl<B> ReadDma<B, u8> for $RxDmaX
-            where
-                B: WriteBuffer<Word = u8>,
-            {
-                fn read(mut self, mut buffer: B) -> Transfer<W, B, Self> {
-                    // NOTE(unsafe) We own the buffer now and we won't call other `&mut` on it
-                    // until the end of the transfer.
-                    let (ptr, len) = unsafe { buffer.write_buffer() };
-                    self.channel
-                        .set_peripheral_address(unsafe { &(*<$USARTX>::ptr()).data as *const _ as u32 }, false);
-                    self.channel.set_memory_address(ptr as u32, true);
-                    self.channel.set_transfer_length(len);
+                /// Stops receiving, and returns the buffers and the RX DMA.
+                pub fn stop(mut self) -> (&'static mut [[u8; N]; 2], $RxDmaX) {
+                    self.payload.channel.stop();
+                    unsafe { &*self.payload.payload.usart }
+                        .ctl0
+                        .modify(|_, w| w.idleie().disabled());
+                    (self.buffers, self.payload)
+                }
 
+                /// Starts the DMA transfer into the active buffer.
+                fn restart(&mut self) {
+                    let buffer = &mut self.buffers[self.active];
+                    self.payload
+                        .channel
+                        .set_memory_address(buffer.as_mut_ptr() as u32, true);
+                    self.payload.channel.set_transfer_length(N);
                     atomic::compiler_fence(Ordering::Release);
-                    self.channel
-                        .configure_from_peripheral(Priority::Medium, Width::Bits8, Width::Bits8, false);
-                    self.start();
-
-                    Transfer::w(buffer, self)
+                    self.payload.channel.start();
                 }
             }
 
-            impl<B> WriteDma<B, u8> for $TxDmaX
-            where
-                B: ReadBuffer<Word = u8>,
-            {
-                fn write(mut self, buffer: B) -> Transfer<R, B, Self> {
-                    // Clear transmission complete bit.
-                    unsafe { &*self.payload.usart }
-                        .stat0
-                        .write(|w| w.tc().clear_bit());
-
-                    // NOTE(unsafe) We own the buffer now and we won't call other `&mut` on it
-                    // until the end of the transfer.
-                    let (ptr, len) = unsafe { buffer.read_buffer() };
-
-                    self.channel
-                        .set_peripheral_address(unsafe { &(*<$USARTX>::ptr()).data as *const _ as u32 }, false);
+            impl<DE: OutputPin<Error = Infallible>> Rs485<$TxDmaX, DE> {
+                pub fn new(tx: $TxDmaX, mut de: DE) -> Self {
+                    de.set_low().unwrap();
+                    Self {
+                        usart: tx.payload.usart,
+                        tx,
+                        de,
+                        asserted: false,
+                        interrupt: false,
+                    }
+                }
 
-                    self.channel.set_memory_address(ptr as u32, true);
-                    self.channel.set_transfer_length(len);
+                /// Asserts the driver enable and starts a DMA transfer.
+                pub fn write<B, WORD>(mut self, buffer: B) -> Rs485<Transfer<R, B, $TxDmaX>, DE>
+                where
+                    B: ReadBuffer<Word = WORD>,
+                    $TxDmaX: WriteDma<B, WORD>,
+                {
+                    self.assert_driver();
+                    let Self { tx, de, usart, asserted, interrupt } = self;
+                    let mut rs485 = Rs485 {
+                        tx: tx.write(buffer),
+                        de,
+                        usart,
+                        asserted,
+                        interrupt,
+                    };
+                    rs485.listen_complete();
+                    rs485
+                }
+            }
 
-                    atomic::compiler_fence(Ordering::Release);
+            impl<B, DE: OutputPin<Error = Infallible>> Rs485<Transfer<R, B, $TxDmaX>, DE> {
+                /// Returns whether the transfer is complete and the driver enable deasserted.
+                pub fn is_done(&mut self) -> bool {
+                    self.tx.is_done() && self.deassert_if_complete()
+                }
 
-                    self.channel
-                        .configure_to_peripheral(Priority::Medium, Width::Bits8, Width::Bits8, false);
-                    self.start();
+                /// Waits for the transfer to complete, then deasserts the driver enable.
+                pub fn wait(self) -> (B, Rs485<$TxDmaX, DE>) {
+                    let Self { tx, de, usart, asserted, interrupt } = self;
+                    let (buffer, tx) = tx.wait();
+                    let mut rs485 = Rs485 {
+                        tx,
+                        de,
+                        usart,
+                        asserted,
+                        interrupt,
+                    };
+                    while !rs485.deassert_if_complete() {}
+                    (buffer, rs485)
+                }
+            }
 
-                    Transfer::r(buffer, self)
+            impl $TxDmaX {
+                pub fn split(mut self) -> (Tx<$USARTX>, $dmatxch) {
+                    self.stop();
+                    let TxDma { payload, channel } = self;
+                    unsafe { &*payload.usart }
+                        .ctl2
+                        .modify(|_, w| w.dent().disabled());
+                    (payload, channel)
                 }
             }
+
+            serialdma_word!($USARTX, $RxDmaX, $TxDmaX, u8, Width::Bits8);
+            serialdma_word!($USARTX, $RxDmaX, $TxDmaX, u16, Width::Bits16);
         )+
     }
 }
@@ -630,16 +2320,25 @@ serialdma! {
     pac::USART0: (
         RxDma0,
         TxDma0,
-        dma::C2,
-        dma::C1,
+        dma0::C4,
+        dma0::C3,
     ),
-}
-
-serialdma! {
     pac::USART1: (
         RxDma1,
         TxDma1,
-        dma::C4,
-        dma::C3,
+        dma0::C5,
+        dma0::C6,
+    ),
+    pac::USART2: (
+        RxDma2,
+        TxDma2,
+        dma0::C2,
+        dma0::C1,
+    ),
+    pac::UART3: (
+        RxDma3,
+        TxDma3,
+        dma1::C2,
+        dma1::C4,
     ),
 }
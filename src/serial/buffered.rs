@@ -0,0 +1,414 @@
+// Copyright 2021 The gd32f1x0-hal authors.
+//
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Interrupt-driven serial communication through ring buffers.
+
+use super::{Error, Instance, RxPin, Serial, TxPin, UsartReadWrite, UsartRegisterBlock};
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+
+/// A single-producer single-consumer ring buffer, which can hold up to `N - 1` bytes.
+struct RingBuffer<const N: usize> {
+    buffer: UnsafeCell<[u8; N]>,
+    read: AtomicUsize,
+    write: AtomicUsize,
+}
+
+impl<const N: usize> RingBuffer<N> {
+    const fn new() -> Self {
+        Self {
+            buffer: UnsafeCell::new([0; N]),
+            read: AtomicUsize::new(0),
+            write: AtomicUsize::new(0),
+        }
+    }
+
+    /// Adds a byte to the buffer, returning false if it is full. Must only be called by the
+    /// producer.
+    fn push(&self, byte: u8) -> bool {
+        let write = self.write.load(Ordering::Relaxed);
+        let next = (write + 1) % N;
+        if next == self.read.load(Ordering::Acquire) {
+            return false;
+        }
+        // NOTE(unsafe) Only the producer writes to the slot at `write`, and the consumer won't read
+        // it until `write` is advanced.
+        unsafe { (*self.buffer.get())[write] = byte };
+        self.write.store(next, Ordering::Release);
+        true
+    }
+
+    /// Removes a byte from the buffer, if it isn't empty. Must only be called by the consumer.
+    fn pop(&self) -> Option<u8> {
+        let read = self.read.load(Ordering::Relaxed);
+        if read == self.write.load(Ordering::Acquire) {
+            return None;
+        }
+        // NOTE(unsafe) Only the consumer reads the slot at `read`, and the producer won't write it
+        // until `read` is advanced.
+        let byte = unsafe { (*self.buffer.get())[read] };
+        self.read.store((read + 1) % N, Ordering::Release);
+        Some(byte)
+    }
+
+    /// Discards the contents of the buffer. Must only be called when neither the producer nor the
+    /// consumer is using it.
+    fn clear(&self) {
+        self.read.store(0, Ordering::Relaxed);
+        self.write.store(0, Ordering::Relaxed);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.read.load(Ordering::Acquire) == self.write.load(Ordering::Acquire)
+    }
+
+    fn len(&self) -> usize {
+        let read = self.read.load(Ordering::Acquire);
+        let write = self.write.load(Ordering::Acquire);
+        (write + N - read) % N
+    }
+}
+
+/// The XON control character, which resumes transmission.
+pub const XON: u8 = 0x11;
+/// The XOFF control character, which pauses transmission.
+pub const XOFF: u8 = 0x13;
+
+/// Receive buffer watermarks for XON/XOFF software flow control.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct XonXoff {
+    /// XOFF is sent when the receive buffer holds at least this many bytes.
+    pub high: usize,
+    /// XON is sent when the receive buffer has been drained to this many bytes after XOFF.
+    pub low: usize,
+}
+
+/// The state of XON/XOFF software flow control, shared with the interrupt handler.
+struct FlowControlState {
+    enabled: AtomicBool,
+    high: AtomicUsize,
+    low: AtomicUsize,
+    /// Whether the other end has paused transmission with XOFF.
+    paused: AtomicBool,
+    /// Whether XOFF has been sent, and XON must be sent once the receive buffer is drained.
+    xoff_sent: AtomicBool,
+    /// A control character waiting to be sent ahead of the transmit buffer, or 0 if none.
+    control: AtomicU8,
+}
+
+impl FlowControlState {
+    const fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            high: AtomicUsize::new(0),
+            low: AtomicUsize::new(0),
+            paused: AtomicBool::new(false),
+            xoff_sent: AtomicBool::new(false),
+            control: AtomicU8::new(0),
+        }
+    }
+}
+
+/// Storage for a [`BufferedSerial`], with a receive buffer of `RX - 1` bytes and a transmit buffer
+/// of `TX - 1` bytes.
+///
+/// This is intended to be declared as a `static`, and can only be used for one serial port.
+pub struct SerialBuffers<const RX: usize, const TX: usize> {
+    rx: RingBuffer<RX>,
+    tx: RingBuffer<TX>,
+    overruns: AtomicUsize,
+    taken: AtomicBool,
+    flow_control: FlowControlState,
+}
+
+// NOTE(unsafe) The ring buffers are only accessed by one BufferedSerial and its interrupt handler,
+// each of which is either the producer or the consumer.
+unsafe impl<const RX: usize, const TX: usize> Sync for SerialBuffers<RX, TX> {}
+
+impl<const RX: usize, const TX: usize> SerialBuffers<RX, TX> {
+    pub const fn new() -> Self {
+        Self {
+            rx: RingBuffer::new(),
+            tx: RingBuffer::new(),
+            overruns: AtomicUsize::new(0),
+            taken: AtomicBool::new(false),
+            flow_control: FlowControlState::new(),
+        }
+    }
+}
+
+impl<const RX: usize, const TX: usize> SerialBuffers<RX, TX> {
+    /// Empties the buffers and resets the flow control state, so they can be used again. Must only
+    /// be called once the interrupt handler no longer uses them.
+    fn reset(&self) {
+        self.rx.clear();
+        self.tx.clear();
+        self.overruns.store(0, Ordering::Relaxed);
+        let flow_control = &self.flow_control;
+        flow_control.enabled.store(false, Ordering::Relaxed);
+        flow_control.paused.store(false, Ordering::Relaxed);
+        flow_control.xoff_sent.store(false, Ordering::Relaxed);
+        flow_control.control.store(0, Ordering::Relaxed);
+        self.taken.store(false, Ordering::Release);
+    }
+}
+
+impl<const RX: usize, const TX: usize> Default for SerialBuffers<RX, TX> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Serial abstraction which reads and writes through ring buffers, which are filled and drained by
+/// [`BufferedSerialInterrupt::on_interrupt`].
+pub struct BufferedSerial<USART, TXPIN, RXPIN, const RX: usize, const TX: usize> {
+    serial: Serial<USART, TXPIN, RXPIN>,
+    buffers: &'static SerialBuffers<RX, TX>,
+}
+
+/// The interrupt handler half of a [`BufferedSerial`].
+pub struct BufferedSerialInterrupt<USART, const RX: usize, const TX: usize> {
+    usart: *const UsartRegisterBlock,
+    buffers: &'static SerialBuffers<RX, TX>,
+    _instance: PhantomData<USART>,
+}
+
+unsafe impl<USART, const RX: usize, const TX: usize> Send
+    for BufferedSerialInterrupt<USART, RX, TX>
+{
+}
+
+impl<USART: Instance, TXPIN: TxPin<USART>, RXPIN: RxPin<USART>> Serial<USART, TXPIN, RXPIN> {
+    /// Switches to interrupt-driven communication through the given buffers, and enables the RBNE
+    /// interrupt.
+    ///
+    /// The USART interrupt must be unmasked in the NVIC, and its handler must call
+    /// [`BufferedSerialInterrupt::on_interrupt`].
+    ///
+    /// Panics if the buffers are already in use, until they are released with
+    /// [`BufferedSerial::release`].
+    pub fn into_buffered<const RX: usize, const TX: usize>(
+        self,
+        buffers: &'static SerialBuffers<RX, TX>,
+    ) -> (
+        BufferedSerial<USART, TXPIN, RXPIN, RX, TX>,
+        BufferedSerialInterrupt<USART, RX, TX>,
+    ) {
+        assert!(!buffers.taken.swap(true, Ordering::AcqRel));
+        let usart: *const UsartRegisterBlock = self.usart.registers();
+        self.usart
+            .registers()
+            .ctl0
+            .modify(|_, w| w.rbneie().enabled());
+        (
+            BufferedSerial {
+                serial: self,
+                buffers,
+            },
+            BufferedSerialInterrupt {
+                usart,
+                buffers,
+                _instance: PhantomData,
+            },
+        )
+    }
+}
+
+impl<USART: Instance, TXPIN, RXPIN, const RX: usize, const TX: usize>
+    BufferedSerial<USART, TXPIN, RXPIN, RX, TX>
+{
+    /// Reads as many received bytes as are available and fit in `buffer`, returning the number of
+    /// bytes read.
+    pub fn read(&mut self, buffer: &mut [u8]) -> usize {
+        let mut count = 0;
+        for slot in buffer.iter_mut() {
+            match self.buffers.rx.pop() {
+                Some(byte) => *slot = byte,
+                None => break,
+            }
+            count += 1;
+        }
+
+        let flow_control = &self.buffers.flow_control;
+        if flow_control.enabled.load(Ordering::Relaxed)
+            && flow_control.xoff_sent.load(Ordering::Relaxed)
+        {
+            // The interrupt handler may fill the buffer and queue XOFF at any point, so XON is
+            // queued with interrupts disabled to avoid overwriting it.
+            cortex_m::interrupt::free(|_| {
+                if self.buffers.rx.len() <= flow_control.low.load(Ordering::Relaxed)
+                    && flow_control.xoff_sent.swap(false, Ordering::Relaxed)
+                {
+                    self.send_control(XON);
+                }
+            });
+        }
+        count
+    }
+
+    /// Queues as many bytes from `data` as fit in the transmit buffer, returning the number of
+    /// bytes queued.
+    pub fn write(&mut self, data: &[u8]) -> usize {
+        let count = data
+            .iter()
+            .take_while(|&&byte| self.buffers.tx.push(byte))
+            .count();
+        if count > 0 {
+            self.serial
+                .usart
+                .registers()
+                .ctl0
+                .modify(|_, w| w.tbeie().enabled());
+        }
+        count
+    }
+
+    /// Enables or disables XON/XOFF software flow control.
+    ///
+    /// When enabled, XON and XOFF received from the other end resume and pause transmission, and
+    /// are not placed in the receive buffer. XOFF is sent when the receive buffer fills up to the
+    /// high watermark, and XON once it has been read down to the low watermark.
+    pub fn set_software_flow_control(&mut self, xon_xoff: Option<XonXoff>) {
+        let flow_control = &self.buffers.flow_control;
+        if let Some(XonXoff { high, low }) = xon_xoff {
+            assert!(low < high && high < RX);
+            flow_control.high.store(high, Ordering::Relaxed);
+            flow_control.low.store(low, Ordering::Relaxed);
+        }
+        flow_control
+            .enabled
+            .store(xon_xoff.is_some(), Ordering::Release);
+
+        if xon_xoff.is_none() {
+            flow_control.paused.store(false, Ordering::Relaxed);
+            // The interrupt handler doesn't queue XOFF once flow control is disabled.
+            if flow_control.xoff_sent.swap(false, Ordering::Relaxed) {
+                self.send_control(XON);
+            }
+            self.serial
+                .usart
+                .registers()
+                .ctl0
+                .modify(|_, w| w.tbeie().enabled());
+        }
+    }
+
+    /// Returns whether the other end has paused transmission with XOFF.
+    pub fn is_paused(&self) -> bool {
+        self.buffers.flow_control.paused.load(Ordering::Relaxed)
+    }
+
+    /// Sends a control character ahead of any queued bytes.
+    fn send_control(&mut self, control: u8) {
+        self.buffers
+            .flow_control
+            .control
+            .store(control, Ordering::Release);
+        self.serial
+            .usart
+            .registers()
+            .ctl0
+            .modify(|_, w| w.tbeie().enabled());
+    }
+
+    /// Returns `WouldBlock` until all queued bytes have been transmitted.
+    pub fn flush(&mut self) -> nb::Result<(), core::convert::Infallible> {
+        if !self.buffers.tx.is_empty() {
+            return Err(nb::Error::WouldBlock);
+        }
+        self.serial.usart.registers().flush()
+    }
+
+    /// Returns the number of received bytes lost, either because the receive buffer was full or
+    /// because of a hardware overrun.
+    pub fn overruns(&self) -> usize {
+        self.buffers.overruns.load(Ordering::Relaxed)
+    }
+
+    /// Switches back to blocking communication, disabling the RBNE and TBE interrupts, and
+    /// returns the `Serial`. The buffers are emptied and can then be used again.
+    ///
+    /// Any bytes still in the buffers are discarded, so [`BufferedSerial::flush`] should be
+    /// called first to wait for queued bytes to be transmitted.
+    pub fn release(
+        self,
+        _interrupt: BufferedSerialInterrupt<USART, RX, TX>,
+    ) -> Serial<USART, TXPIN, RXPIN> {
+        self.serial
+            .usart
+            .registers()
+            .ctl0
+            .modify(|_, w| w.rbneie().disabled().tbeie().disabled());
+        self.buffers.reset();
+        self.serial
+    }
+}
+
+impl<USART: Instance, TXPIN, RXPIN, const RX: usize, const TX: usize> fmt::Write
+    for BufferedSerial<USART, TXPIN, RXPIN, RX, TX>
+{
+    /// Queues the whole string, waiting for space in the transmit buffer as necessary.
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut data = s.as_bytes();
+        while !data.is_empty() {
+            let count = self.write(data);
+            data = &data[count..];
+        }
+        Ok(())
+    }
+}
+
+impl<USART: Instance, const RX: usize, const TX: usize> BufferedSerialInterrupt<USART, RX, TX> {
+    /// Moves a received byte into the receive buffer and the next queued byte out of the transmit
+    /// buffer. This must be called from the USART interrupt handler.
+    ///
+    /// Bytes received with framing, noise or parity errors are discarded.
+    pub fn on_interrupt(&mut self) {
+        // NOTE(unsafe) This points to the registers of the USART owned by the BufferedSerial.
+        let usart = unsafe { &*self.usart };
+
+        let flow_control = &self.buffers.flow_control;
+        let flow_control_enabled = flow_control.enabled.load(Ordering::Acquire);
+        match usart.read() {
+            Ok(word) if flow_control_enabled && word == u16::from(XOFF) => {
+                flow_control.paused.store(true, Ordering::Relaxed);
+            }
+            Ok(word) if flow_control_enabled && word == u16::from(XON) => {
+                flow_control.paused.store(false, Ordering::Relaxed);
+                usart.ctl0.modify(|_, w| w.tbeie().enabled());
+            }
+            Ok(word) => {
+                if !self.buffers.rx.push(word as u8) {
+                    self.buffers.overruns.fetch_add(1, Ordering::Relaxed);
+                }
+                if flow_control_enabled
+                    && self.buffers.rx.len() >= flow_control.high.load(Ordering::Relaxed)
+                    && !flow_control.xoff_sent.swap(true, Ordering::Relaxed)
+                {
+                    flow_control.control.store(XOFF, Ordering::Release);
+                    usart.ctl0.modify(|_, w| w.tbeie().enabled());
+                }
+            }
+            Err(nb::Error::Other(Error::Overrun)) => {
+                self.buffers.overruns.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(_) => {}
+        }
+
+        if usart.ctl0.read().tbeie().is_enabled() && usart.stat0.read().tbe().bit_is_set() {
+            // Control characters are sent even while paused.
+            let byte = match flow_control.control.swap(0, Ordering::Acquire) {
+                0 if flow_control.paused.load(Ordering::Relaxed) => None,
+                0 => self.buffers.tx.pop(),
+                control => Some(control),
+            };
+            match byte {
+                Some(byte) => usart.data.write(|w| unsafe { w.data().bits(byte.into()) }),
+                None => usart.ctl0.modify(|_, w| w.tbeie().disabled()),
+            }
+        }
+    }
+}
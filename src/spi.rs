@@ -3,21 +3,22 @@
   To construct the SPI instances, use the `Spi::spiX` functions.
 
   The pin parameter is a tuple containing `(sck, miso, mosi)` which should be configured as `(Alternate<PushPull>, Input<Floating>, Alternate<PushPull>)`.
-  As some STM32F1xx chips have 5V tolerant SPI pins, it is also possible to configure Sck and Mosi outputs as `Alternate<PushPull>`. Then
+  As some GD32E103 pins are 5V tolerant, it is also possible to configure Sck and Mosi outputs as `Alternate<OpenDrain>`. Then
   a simple Pull-Up to 5V can be used to use SPI on a 5V bus without a level shifter.
 
   You can also use `NoSck`, `NoMiso` or `NoMosi` if you don't want to use the pins
 
-  - `SPI1` can use `(PA5, PA6, PA7)` or `(PB3, PB4, PB5)`.
-  - `SPI2` can use `(PB13, PB14, PB15)`
-  - `SPI3` can use `(PB3, PB4, PB5)` or only in connectivity line devices `(PC10, PC11, PC12)`
+  - `SPI0` can use `(PA5, PA6, PA7)` or `(PB3, PB4, PB5)`.
+  - `SPI1` can use `(PB13, PB14, PB15)`
+  - `SPI2` can use `(PB3, PB4, PB5)` or `(PC10, PC11, PC12)`
 
+  PB3 and PB4 are JTAG pins after reset, so JTAG must be disabled in `AFIO_PCF0` before using them.
 
   ## Initialisation example
 
   ```rust
     // Acquire the GPIOB peripheral
-    let mut gpiob = dp.GPIOB.split(&mut rcc.apb2);
+    let mut gpiob = dp.GPIOB.split(&mut rcu.apb2);
 
     let pins = (
         gpiob.pb13.into_alternate_push_pull(&mut gpiob.crh),
@@ -29,29 +30,23 @@
         polarity: Polarity::IdleLow,
         phase: Phase::CaptureOnFirstTransition,
     };
-    let spi = Spi::spi2(dp.SPI2, pins, spi_mode, 100.khz(), clocks, &mut rcc.apb1);
+    let spi = Spi::spi1(dp.SPI1, pins, spi_mode, 100.khz(), clocks, &mut rcu.apb1);
   ```
 */
 
 use core::ops::Deref;
 use core::ptr;
 
-pub use crate::hal::spi::{FullDuplex, Mode, Phase, Polarity};
-#[cfg(any(feature = "high", feature = "connectivity"))]
-use crate::pac::SPI3;
-use crate::pac::{SPI1, SPI2};
+use crate::pac::{AFIO, SPI0, SPI1, SPI2};
+pub use embedded_hal::spi::{FullDuplex, Mode, Phase, Polarity};
 
-use crate::afio::MAPR;
-use crate::dma::dma1::{C3, C5};
-#[cfg(feature = "connectivity")]
-use crate::dma::dma2::C2;
-use crate::dma::{Transfer, TransferPayload, Transmit, TxDma, R};
+use crate::dma::{dma0, dma1};
+use crate::dma::{Priority, Transfer, TransferPayload, Transmit, TxDma, Width, R};
 use crate::gpio::gpioa::{PA5, PA6, PA7};
 use crate::gpio::gpiob::{PB13, PB14, PB15, PB3, PB4, PB5};
-#[cfg(feature = "connectivity")]
 use crate::gpio::gpioc::{PC10, PC11, PC12};
 use crate::gpio::{Alternate, Floating, Input, OpenDrain, PushPull};
-use crate::rcc::{Clocks, Enable, GetBusFreq, Reset, APB1, APB2};
+use crate::rcu::{Clocks, Enable, GetBusFreq, Reset, APB1, APB2};
 use crate::time::Hertz;
 
 use core::sync::atomic::{self, Ordering};
@@ -153,26 +148,24 @@ macro_rules! remap {
     };
 }
 
-remap!(Spi1NoRemap, SPI1, false, PA5, PA6, PA7);
-remap!(Spi1Remap, SPI1, true, PB3, PB4, PB5);
-remap!(Spi2NoRemap, SPI2, false, PB13, PB14, PB15);
-#[cfg(any(feature = "high", feature = "connectivity"))]
-remap!(Spi3NoRemap, SPI3, false, PB3, PB4, PB5);
-#[cfg(feature = "connectivity")]
-remap!(Spi3Remap, SPI3, true, PC10, PC11, PC12);
+remap!(Spi0NoRemap, SPI0, false, PA5, PA6, PA7);
+remap!(Spi0Remap, SPI0, true, PB3, PB4, PB5);
+remap!(Spi1NoRemap, SPI1, false, PB13, PB14, PB15);
+remap!(Spi2NoRemap, SPI2, false, PB3, PB4, PB5);
+remap!(Spi2Remap, SPI2, true, PC10, PC11, PC12);
 
-impl<REMAP, PINS> Spi<SPI1, REMAP, PINS, u8> {
+impl<REMAP, PINS> Spi<SPI0, REMAP, PINS, u8> {
     /**
-      Constructs an SPI instance using SPI1 in 8bit dataframe mode.
+      Constructs an SPI instance using SPI0 in 8bit dataframe mode.
 
       The pin parameter tuple (sck, miso, mosi) should be `(PA5, PA6, PA7)` or `(PB3, PB4, PB5)` configured as `(Alternate<PushPull>, Input<Floating>, Alternate<PushPull>)`.
 
       You can also use `NoSck`, `NoMiso` or `NoMosi` if you don't want to use the pins
     */
-    pub fn spi1<F, POS>(
-        spi: SPI1,
+    pub fn spi0<F, POS>(
+        spi: SPI0,
         pins: PINS,
-        mapr: &mut MAPR,
+        afio: &mut AFIO,
         mode: Mode,
         freq: F,
         clocks: Clocks,
@@ -180,24 +173,24 @@ impl<REMAP, PINS> Spi<SPI1, REMAP, PINS, u8> {
     ) -> Self
     where
         F: Into<Hertz>,
-        REMAP: Remap<Periph = SPI1>,
+        REMAP: Remap<Periph = SPI0>,
         PINS: Pins<REMAP, POS>,
     {
-        mapr.modify_mapr(|_, w| w.spi1_remap().bit(REMAP::REMAP));
-        Spi::<SPI1, _, _, u8>::_spi(spi, pins, mode, freq.into(), clocks, apb)
+        afio.pcf0.modify(|_, w| w.spi0_remap().bit(REMAP::REMAP));
+        Spi::<SPI0, _, _, u8>::create_internal(spi, pins, mode, freq.into(), clocks, apb)
     }
 }
 
-impl<REMAP, PINS> Spi<SPI2, REMAP, PINS, u8> {
+impl<REMAP, PINS> Spi<SPI1, REMAP, PINS, u8> {
     /**
-      Constructs an SPI instance using SPI2 in 8bit dataframe mode.
+      Constructs an SPI instance using SPI1 in 8bit dataframe mode.
 
       The pin parameter tuple (sck, miso, mosi) should be `(PB13, PB14, PB15)` configured as `(Alternate<PushPull>, Input<Floating>, Alternate<PushPull>)`.
 
       You can also use `NoSck`, `NoMiso` or `NoMosi` if you don't want to use the pins
     */
-    pub fn spi2<F, POS>(
-        spi: SPI2,
+    pub fn spi1<F, POS>(
+        spi: SPI1,
         pins: PINS,
         mode: Mode,
         freq: F,
@@ -206,51 +199,25 @@ impl<REMAP, PINS> Spi<SPI2, REMAP, PINS, u8> {
     ) -> Self
     where
         F: Into<Hertz>,
-        REMAP: Remap<Periph = SPI2>,
+        REMAP: Remap<Periph = SPI1>,
         PINS: Pins<REMAP, POS>,
     {
-        Spi::<SPI2, _, _, u8>::_spi(spi, pins, mode, freq.into(), clocks, apb)
+        Spi::<SPI1, _, _, u8>::create_internal(spi, pins, mode, freq.into(), clocks, apb)
     }
 }
 
-#[cfg(any(feature = "high", feature = "connectivity"))]
-impl<REMAP, PINS> Spi<SPI3, REMAP, PINS, u8> {
-    /**
-      Constructs an SPI instance using SPI3 in 8bit dataframe mode.
-
-      The pin parameter tuple (sck, miso, mosi) should be `(PB3, PB4, PB5)` configured as `(Alternate<PushPull>, Input<Floating>, Alternate<PushPull>)`.
-
-      You can also use `NoSck`, `NoMiso` or `NoMosi` if you don't want to use the pins
-    */
-    #[cfg(not(feature = "connectivity"))]
-    pub fn spi3<F, POS>(
-        spi: SPI3,
-        pins: PINS,
-        mode: Mode,
-        freq: F,
-        clocks: Clocks,
-        apb: &mut APB1,
-    ) -> Self
-    where
-        F: Into<Hertz>,
-        REMAP: Remap<Periph = SPI3>,
-        PINS: Pins<REMAP, POS>,
-    {
-        Spi::<SPI3, _, _, u8>::_spi(spi, pins, mode, freq.into(), clocks, apb)
-    }
-
+impl<REMAP, PINS> Spi<SPI2, REMAP, PINS, u8> {
     /**
-      Constructs an SPI instance using SPI3 in 8bit dataframe mode.
+      Constructs an SPI instance using SPI2 in 8bit dataframe mode.
 
       The pin parameter tuple (sck, miso, mosi) should be `(PB3, PB4, PB5)` or `(PC10, PC11, PC12)` configured as `(Alternate<PushPull>, Input<Floating>, Alternate<PushPull>)`.
 
       You can also use `NoSck`, `NoMiso` or `NoMosi` if you don't want to use the pins
     */
-    #[cfg(feature = "connectivity")]
-    pub fn spi3<F, POS>(
-        spi: SPI3,
+    pub fn spi2<F, POS>(
+        spi: SPI2,
         pins: PINS,
-        mapr: &mut MAPR,
+        afio: &mut AFIO,
         mode: Mode,
         freq: F,
         clocks: Clocks,
@@ -258,15 +225,15 @@ impl<REMAP, PINS> Spi<SPI3, REMAP, PINS, u8> {
     ) -> Self
     where
         F: Into<Hertz>,
-        REMAP: Remap<Periph = SPI3>,
+        REMAP: Remap<Periph = SPI2>,
         PINS: Pins<REMAP, POS>,
     {
-        mapr.modify_mapr(|_, w| w.spi3_remap().bit(REMAP::REMAP));
-        Spi::<SPI3, _, _, u8>::_spi(spi, pins, mode, freq.into(), clocks, apb)
+        afio.pcf0.modify(|_, w| w.spi2_remap().bit(REMAP::REMAP));
+        Spi::<SPI2, _, _, u8>::create_internal(spi, pins, mode, freq.into(), clocks, apb)
     }
 }
 
-pub type SpiRegisterBlock = crate::pac::spi1::RegisterBlock;
+pub type SpiRegisterBlock = crate::pac::spi0::RegisterBlock;
 
 pub trait SpiReadWrite<T> {
     fn read_data_reg(&mut self) -> T;
@@ -280,57 +247,39 @@ where
     FrameSize: Copy,
 {
     fn read_data_reg(&mut self) -> FrameSize {
-        // NOTE(read_volatile) read only 1 byte (the svd2rust API only allows
-        // reading a half-word)
-        unsafe { ptr::read_volatile(&self.spi.dr as *const _ as *const FrameSize) }
+        // NOTE(read_volatile) read only the frame size (the svd2rust API only allows
+        // reading the whole word)
+        unsafe { ptr::read_volatile(self.spi.data.as_ptr() as *const FrameSize) }
     }
 
     fn write_data_reg(&mut self, data: FrameSize) {
         // NOTE(write_volatile) see note above
-        unsafe { ptr::write_volatile(&self.spi.dr as *const _ as *mut FrameSize, data) }
+        unsafe { ptr::write_volatile(self.spi.data.as_ptr() as *mut FrameSize, data) }
     }
 
-    // Implement write as per the "Transmit only procedure" page 712
-    // of RM0008 Rev 20. This is more than twice as fast as the
-    // default Write<> implementation (which reads and drops each
-    // received value)
+    // Transmit without waiting for each received value, which is more than twice as fast as the
+    // default Write<> implementation (which reads and drops each received value)
     fn spi_write(&mut self, words: &[FrameSize]) -> Result<(), Error> {
         // Write each word when the tx buffer is empty
         for word in words {
             loop {
-                let sr = self.spi.sr.read();
-                if sr.txe().bit_is_set() {
-                    // NOTE(write_volatile) see note above
-                    // unsafe { ptr::write_volatile(&self.spi.dr as *const _ as *mut u8, *word) }
+                let stat = self.spi.stat.read();
+                if stat.tbe().bit_is_set() {
                     self.write_data_reg(*word);
-                    if sr.modf().bit_is_set() {
+                    if stat.conferr().bit_is_set() {
                         return Err(Error::ModeFault);
                     }
                     break;
                 }
             }
         }
-        // Wait for final TXE
-        loop {
-            let sr = self.spi.sr.read();
-            if sr.txe().bit_is_set() {
-                break;
-            }
-        }
-        // Wait for final !BSY
-        loop {
-            let sr = self.spi.sr.read();
-            if !sr.bsy().bit_is_set() {
-                break;
-            }
-        }
-        // Clear OVR set due to dropped received values
-        // NOTE(read_volatile) see note above
-        // unsafe {
-        //     let _ = ptr::read_volatile(&self.spi.dr as *const _ as *const u8);
-        // }
+        // Wait for final TBE
+        while self.spi.stat.read().tbe().bit_is_clear() {}
+        // Wait for the last frame to be shifted out
+        while self.spi.stat.read().trans().is_busy() {}
+        // Clear RXORERR set due to dropped received values
         let _ = self.read_data_reg();
-        let _ = self.spi.sr.read();
+        let _ = self.spi.stat.read();
         Ok(())
     }
 }
@@ -340,10 +289,7 @@ where
     SPI: Deref<Target = SpiRegisterBlock>,
     FrameSize: Copy,
 {
-    #[deprecated(since = "0.6.0", note = "Please use release instead")]
-    pub fn free(self) -> (SPI, PINS) {
-        self.release()
-    }
+    /// Releases the SPI peripheral and associated pins
     pub fn release(self) -> (SPI, PINS) {
         (self.spi, self.pins)
     }
@@ -351,8 +297,8 @@ where
     /// Select which frame format is used for data transfers
     pub fn bit_format(&mut self, format: SpiBitFormat) {
         match format {
-            SpiBitFormat::LsbFirst => self.spi.cr1.modify(|_, w| w.lsbfirst().set_bit()),
-            SpiBitFormat::MsbFirst => self.spi.cr1.modify(|_, w| w.lsbfirst().clear_bit()),
+            SpiBitFormat::LsbFirst => self.spi.ctl0.modify(|_, w| w.lf().lsbfirst()),
+            SpiBitFormat::MsbFirst => self.spi.ctl0.modify(|_, w| w.lf().msbfirst()),
         }
     }
 }
@@ -362,7 +308,7 @@ where
     SPI: Deref<Target = SpiRegisterBlock> + Enable + Reset,
     SPI::Bus: GetBusFreq,
 {
-    fn _spi(
+    fn create_internal(
         spi: SPI,
         pins: PINS,
         mode: Mode,
@@ -374,12 +320,12 @@ where
         SPI::enable(apb);
         SPI::reset(apb);
 
-        // disable SS output
-        spi.cr2.write(|w| w.ssoe().clear_bit());
+        // disable NSS output
+        spi.ctl1.write(|w| w.nssdrv().disabled());
 
-        let br = match SPI::Bus::get_frequency(&clocks).0 / freq.0 {
-            0 => unreachable!(),
-            1..=2 => 0b000,
+        let psc = match SPI::Bus::get_frequency(&clocks).0 / freq.0 {
+            // Frequencies above the bus clock get the fastest divider.
+            0..=2 => 0b000,
             3..=5 => 0b001,
             6..=11 => 0b010,
             12..=23 => 0b011,
@@ -389,41 +335,41 @@ where
             _ => 0b111,
         };
 
-        spi.cr1.write(|w| {
+        spi.ctl0.write(|w| {
             w
                 // clock phase from config
-                .cpha()
+                .ckph()
                 .bit(mode.phase == Phase::CaptureOnSecondTransition)
                 // clock polarity from config
-                .cpol()
+                .ckpl()
                 .bit(mode.polarity == Polarity::IdleHigh)
-                // mstr: master configuration
-                .mstr()
-                .set_bit()
-                // baudrate value
-                .br()
-                .bits(br)
-                // lsbfirst: MSB first
-                .lsbfirst()
-                .clear_bit()
-                // ssm: enable software slave management (NSS pin free for other uses)
-                .ssm()
-                .set_bit()
-                // ssi: set nss high = master mode
-                .ssi()
-                .set_bit()
-                // dff: 8 bit frames
-                .dff()
-                .clear_bit()
-                // bidimode: 2-line unidirectional
-                .bidimode()
-                .clear_bit()
+                // master mode
+                .mstmod()
+                .master()
+                // baudrate prescaler
+                .psc()
+                .bits(psc)
+                // MSB first
+                .lf()
+                .msbfirst()
+                // software NSS management (NSS pin free for other uses)
+                .swnssen()
+                .software()
+                // NSS high = master mode
+                .swnss()
+                .slave_not_selected()
+                // 8 bit frames
+                .ff16()
+                .eight_bit()
+                // 2-line unidirectional
+                .bden()
+                .unidirectional()
                 // both TX and RX are used
-                .rxonly()
-                .clear_bit()
-                // spe: enable the SPI bus
-                .spe()
-                .set_bit()
+                .ro()
+                .full_duplex()
+                // enable the SPI bus
+                .spien()
+                .enabled()
         });
 
         Spi {
@@ -435,9 +381,9 @@ where
     }
     /// Converts from 8bit dataframe to 16bit.
     pub fn frame_size_16bit(self) -> Spi<SPI, REMAP, PINS, u16> {
-        self.spi.cr1.modify(|_, w| w.spe().clear_bit());
-        self.spi.cr1.modify(|_, w| w.dff().set_bit());
-        self.spi.cr1.modify(|_, w| w.spe().set_bit());
+        self.spi.ctl0.modify(|_, w| w.spien().disabled());
+        self.spi.ctl0.modify(|_, w| w.ff16().sixteen_bit());
+        self.spi.ctl0.modify(|_, w| w.spien().enabled());
         Spi {
             spi: self.spi,
             pins: self.pins,
@@ -453,9 +399,9 @@ where
 {
     /// Converts from 16bit dataframe to 8bit.
     pub fn frame_size_8bit(self) -> Spi<SPI, REMAP, PINS, u8> {
-        self.spi.cr1.modify(|_, w| w.spe().clear_bit());
-        self.spi.cr1.modify(|_, w| w.dff().clear_bit());
-        self.spi.cr1.modify(|_, w| w.spe().set_bit());
+        self.spi.ctl0.modify(|_, w| w.spien().disabled());
+        self.spi.ctl0.modify(|_, w| w.ff16().eight_bit());
+        self.spi.ctl0.modify(|_, w| w.spien().enabled());
         Spi {
             spi: self.spi,
             pins: self.pins,
@@ -465,7 +411,7 @@ where
     }
 }
 
-impl<SPI, REMAP, PINS, FrameSize> crate::hal::spi::FullDuplex<FrameSize>
+impl<SPI, REMAP, PINS, FrameSize> embedded_hal::spi::FullDuplex<FrameSize>
     for Spi<SPI, REMAP, PINS, FrameSize>
 where
     SPI: Deref<Target = SpiRegisterBlock>,
@@ -474,17 +420,15 @@ where
     type Error = Error;
 
     fn read(&mut self) -> nb::Result<FrameSize, Error> {
-        let sr = self.spi.sr.read();
+        let stat = self.spi.stat.read();
 
-        Err(if sr.ovr().bit_is_set() {
+        Err(if stat.rxorerr().is_overrun() {
             nb::Error::Other(Error::Overrun)
-        } else if sr.modf().bit_is_set() {
+        } else if stat.conferr().is_fault() {
             nb::Error::Other(Error::ModeFault)
-        } else if sr.crcerr().bit_is_set() {
+        } else if stat.crcerr().bit_is_set() {
             nb::Error::Other(Error::Crc)
-        } else if sr.rxne().bit_is_set() {
-            // NOTE(read_volatile) read only 1 byte (the svd2rust API only allows
-            // reading a half-word)
+        } else if stat.rbne().bit_is_set() {
             return Ok(self.read_data_reg());
         } else {
             nb::Error::WouldBlock
@@ -492,15 +436,15 @@ where
     }
 
     fn send(&mut self, data: FrameSize) -> nb::Result<(), Error> {
-        let sr = self.spi.sr.read();
+        let stat = self.spi.stat.read();
 
-        Err(if sr.ovr().bit_is_set() {
+        Err(if stat.rxorerr().is_overrun() {
             nb::Error::Other(Error::Overrun)
-        } else if sr.modf().bit_is_set() {
+        } else if stat.conferr().is_fault() {
             nb::Error::Other(Error::ModeFault)
-        } else if sr.crcerr().bit_is_set() {
+        } else if stat.crcerr().bit_is_set() {
             nb::Error::Other(Error::Crc)
-        } else if sr.txe().bit_is_set() {
+        } else if stat.tbe().bit_is_set() {
             // NOTE(write_volatile) see note above
             self.write_data_reg(data);
             return Ok(());
@@ -510,7 +454,7 @@ where
     }
 }
 
-impl<SPI, REMAP, PINS, FrameSize> crate::hal::blocking::spi::transfer::Default<FrameSize>
+impl<SPI, REMAP, PINS, FrameSize> embedded_hal::blocking::spi::transfer::Default<FrameSize>
     for Spi<SPI, REMAP, PINS, FrameSize>
 where
     SPI: Deref<Target = SpiRegisterBlock>,
@@ -518,22 +462,19 @@ where
 {
 }
 
-impl<SPI, REMAP, PINS> crate::hal::blocking::spi::Write<u8> for Spi<SPI, REMAP, PINS, u8>
+impl<SPI, REMAP, PINS> embedded_hal::blocking::spi::Write<u8> for Spi<SPI, REMAP, PINS, u8>
 where
     SPI: Deref<Target = SpiRegisterBlock>,
 {
     type Error = Error;
 
-    // Implement write as per the "Transmit only procedure" page 712
-    // of RM0008 Rev 20. This is more than twice as fast as the
-    // default Write<> implementation (which reads and drops each
-    // received value)
+    // Transmit without waiting for each received value, see `spi_write`
     fn write(&mut self, words: &[u8]) -> Result<(), Error> {
         self.spi_write(words)
     }
 }
 
-impl<SPI, REMAP, PINS> crate::hal::blocking::spi::Write<u16> for Spi<SPI, REMAP, PINS, u16>
+impl<SPI, REMAP, PINS> embedded_hal::blocking::spi::Write<u16> for Spi<SPI, REMAP, PINS, u16>
 where
     SPI: Deref<Target = SpiRegisterBlock>,
 {
@@ -553,7 +494,7 @@ pub struct SpiPayload<SPI, REMAP, PINS> {
 pub type SpiTxDma<SPI, REMAP, PINS, CHANNEL> = TxDma<SpiPayload<SPI, REMAP, PINS>, CHANNEL>;
 
 macro_rules! spi_dma {
-    ($SPIi:ident, $TCi:ident) => {
+    ($SPIi:ident, $TCi:ty) => {
         impl<REMAP, PINS> Transmit for SpiTxDma<$SPIi, REMAP, PINS, $TCi> {
             type TxChannel = $TCi;
             type ReceivedWord = u8;
@@ -571,16 +512,16 @@ macro_rules! spi_dma {
                 self.payload
                     .spi
                     .spi
-                    .cr2
-                    .modify(|_, w| w.txdmaen().set_bit());
+                    .ctl1
+                    .modify(|_, w| w.dmaten().enabled());
                 self.channel.start();
             }
             fn stop(&mut self) {
                 self.payload
                     .spi
                     .spi
-                    .cr2
-                    .modify(|_, w| w.txdmaen().clear_bit());
+                    .ctl1
+                    .modify(|_, w| w.dmaten().disabled());
                 self.channel.stop();
             }
         }
@@ -593,35 +534,18 @@ macro_rules! spi_dma {
                 // NOTE(unsafe) We own the buffer now and we won't call other `&mut` on it
                 // until the end of the transfer.
                 let (ptr, len) = unsafe { buffer.read_buffer() };
-                self.channel.set_peripheral_address(
-                    unsafe { &(*$SPIi::ptr()).dr as *const _ as u32 },
-                    false,
-                );
+                self.channel
+                    .set_peripheral_address(unsafe { (*$SPIi::ptr()).data.as_ptr() as u32 }, false);
                 self.channel.set_memory_address(ptr as u32, true);
                 self.channel.set_transfer_length(len);
 
                 atomic::compiler_fence(Ordering::Release);
-                self.channel.ch().cr.modify(|_, w| {
-                    w
-                        // memory to memory mode disabled
-                        .mem2mem()
-                        .clear_bit()
-                        // medium channel priority level
-                        .pl()
-                        .medium()
-                        // 8-bit memory size
-                        .msize()
-                        .bits8()
-                        // 8-bit peripheral size
-                        .psize()
-                        .bits8()
-                        // circular mode disabled
-                        .circ()
-                        .clear_bit()
-                        // read from memory
-                        .dir()
-                        .set_bit()
-                });
+                self.channel.configure_to_peripheral(
+                    Priority::Medium,
+                    Width::Bits8,
+                    Width::Bits8,
+                    false,
+                );
                 self.start();
 
                 Transfer::r(buffer, self)
@@ -630,7 +554,6 @@ macro_rules! spi_dma {
     };
 }
 
-spi_dma!(SPI1, C3);
-spi_dma!(SPI2, C5);
-#[cfg(feature = "connectivity")]
-spi_dma!(SPI3, C2);
+spi_dma!(SPI0, dma0::C2);
+spi_dma!(SPI1, dma0::C4);
+spi_dma!(SPI2, dma1::C1);